@@ -130,49 +130,49 @@ fn generic_structs() {
     println!();
 }
 
-fn trait_examples() {
-    println!("3. Traits - Defining Shared Behavior");
-    
-    // Define a trait
-    trait Summary {
-        fn summarize(&self) -> String;
-        
-        // Default implementation
-        fn summarize_author(&self) -> String {
-            String::from("(Read more...)")
-        }
-    }
+// Define a trait (at module level, so trait_bounds() below can use it too)
+trait Summary {
+    fn summarize(&self) -> String;
     
-    // Structs that implement the trait
-    struct NewsArticle {
-        headline: String,
-        location: String,
-        author: String,
-        content: String,
+    // Default implementation
+    fn summarize_author(&self) -> String {
+        String::from("(Read more...)")
     }
-    
-    impl Summary for NewsArticle {
-        fn summarize(&self) -> String {
-            format!("{}, by {} ({})", self.headline, self.author, self.location)
-        }
-        
-        fn summarize_author(&self) -> String {
-            format!("@{}", self.author)
-        }
+}
+
+// Structs that implement the trait
+struct NewsArticle {
+    headline: String,
+    location: String,
+    author: String,
+    content: String,
+}
+
+impl Summary for NewsArticle {
+    fn summarize(&self) -> String {
+        format!("{}, by {} ({})", self.headline, self.author, self.location)
     }
     
-    struct Tweet {
-        username: String,
-        content: String,
-        reply: bool,
-        retweet: bool,
+    fn summarize_author(&self) -> String {
+        format!("@{}", self.author)
     }
-    
-    impl Summary for Tweet {
-        fn summarize(&self) -> String {
-            format!("{}: {}", self.username, self.content)
-        }
+}
+
+struct Tweet {
+    username: String,
+    content: String,
+    reply: bool,
+    retweet: bool,
+}
+
+impl Summary for Tweet {
+    fn summarize(&self) -> String {
+        format!("{}: {}", self.username, self.content)
     }
+}
+
+fn trait_examples() {
+    println!("3. Traits - Defining Shared Behavior");
     
     let article = NewsArticle {
         headline: String::from("Rust 1.70 Released!"),
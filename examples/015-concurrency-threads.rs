@@ -7,9 +7,2118 @@
 // - Shared state concurrency (Mutex, RwLock)
 // - Arc and Mutex for multiple ownership and mutable access
 // - Thread pools and task scheduling
+// - Work stealing and fork/join parallelism
+// - Atomics and memory ordering: a lock-free ring buffer and a spinlock
+// - Classic problems: bounded buffers, barriers, readers/writers, dining philosophers
+// - Spotting a deadlock with a watchdog
 //
-// Placeholder for future content.
+// Run with: cargo run --example 015-concurrency-threads
+// Count the words of your own files: cargo run --example 015-concurrency-threads -- notes.txt *.md
+// Test with: cargo test --example 015-concurrency-threads
+// (the lock_free tests run under loom, which tries every interleaving)
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thread_pool::ThreadPool;
+use work_stealing::Scheduler;
 
 fn main() {
-    println!("Hello from Concurrency and Threads example!");
+    println!("=== Rust Concurrency and Threads Examples ===\n");
+
+    // Spawning threads and waiting for them
+    spawning_threads();
+
+    // Sending values between threads
+    channel_examples();
+
+    // Sharing state between threads
+    shared_state_examples();
+
+    // Reusing a fixed set of threads for many jobs
+    thread_pool_examples();
+
+    // Three ways to split up one job: counting words
+    word_count_examples();
+
+    // Producers and consumers, barriers, readers and writers, philosophers
+    synchronization_examples();
+
+    // Splitting work recursively and letting idle threads steal it
+    work_stealing_examples();
+
+    // What Mutex is made of: atomics and memory ordering
+    lock_free_examples();
+}
+
+fn spawning_threads() {
+    println!("1. Spawning Threads");
+
+    // `move` gives the thread its own copy of `numbers`: it may outlive this function
+    let numbers = [1, 2, 3];
+    let handle = thread::spawn(move || numbers.iter().sum::<i32>());
+    // join() waits for the thread and hands back what its closure returned
+    println!("Sum computed on another thread: {}", handle.join().unwrap());
+
+    // Scoped threads may borrow local variables, because they are joined before the scope ends
+    let words = ["alpha", "beta", "gamma"];
+    let lengths: Vec<usize> = thread::scope(|scope| {
+        let handles: Vec<_> = words.iter().map(|word| scope.spawn(move || word.len())).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    println!("Lengths from scoped threads: {:?}", lengths);
+    println!();
+}
+
+fn channel_examples() {
+    println!("2. Channels (Message Passing)");
+
+    let (sender, receiver) = mpsc::channel();
+    for id in 0..3 {
+        // Every producer gets its own Sender
+        let sender = sender.clone();
+        thread::spawn(move || {
+            sender.send(id * 10).unwrap();
+        });
+    }
+    // The receiving loop ends once every Sender is gone, so drop the original one
+    drop(sender);
+
+    let mut received: Vec<i32> = receiver.iter().collect();
+    // Threads finish in any order; sort for a stable printout
+    received.sort();
+    println!("Received: {:?}", received);
+    println!();
+}
+
+fn shared_state_examples() {
+    println!("3. Shared State (Arc, Mutex, RwLock)");
+
+    // Arc shares ownership between threads; Mutex lets one of them change it at a time
+    let counter = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    *counter.lock().unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("Counter after 8 threads x 1000 increments: {}", *counter.lock().unwrap());
+
+    // RwLock allows many readers at once, or a single writer
+    let config = RwLock::new(String::from("debug"));
+    {
+        let first = config.read().unwrap();
+        let second = config.read().unwrap();
+        println!("Two readers at once: {} and {}", *first, *second);
+    }
+    *config.write().unwrap() = String::from("release");
+    println!("After a write: {}", config.read().unwrap());
+    println!();
+}
+
+fn thread_pool_examples() {
+    println!("4. Thread Pool");
+
+    // Spawning a thread per job is wasteful; a pool starts a few threads and feeds them jobs
+    let pool = ThreadPool::new(4);
+    println!("Pool with {} workers", pool.size());
+
+    let (sender, receiver) = mpsc::channel();
+    for n in 1..=8u64 {
+        let sender = sender.clone();
+        pool.execute(move || {
+            let sum_of_squares: u64 = (1..=n).map(|i| i * i).sum();
+            sender.send((n, sum_of_squares)).unwrap();
+        });
+    }
+    drop(sender);
+    let mut results: Vec<(u64, u64)> = receiver.iter().collect();
+    results.sort();
+    println!("Sums of squares: {:?}", results);
+
+    // Jobs are spread over the workers; no job gets a thread of its own
+    let threads = Arc::new(Mutex::new(Vec::new()));
+    for _ in 0..20 {
+        let threads = Arc::clone(&threads);
+        pool.execute(move || {
+            let id = thread::current().id();
+            let mut threads = threads.lock().unwrap();
+            if !threads.contains(&id) {
+                threads.push(id);
+            }
+        });
+    }
+    // join() waits until every job submitted so far has finished
+    pool.join();
+    println!("20 jobs ran on at most {} threads: {}", pool.size(), threads.lock().unwrap().len() <= pool.size());
+
+    // A panicking job is caught; the worker that ran it keeps going
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {})); // keep the demo's output quiet
+    let finished = Arc::new(AtomicUsize::new(0));
+    pool.execute(|| panic!("this job fails"));
+    for _ in 0..10 {
+        let finished = Arc::clone(&finished);
+        pool.execute(move || {
+            finished.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    pool.join();
+    std::panic::set_hook(default_hook);
+    println!(
+        "Jobs that panicked: {}, jobs that still ran after it: {}",
+        pool.panicked(),
+        finished.load(Ordering::SeqCst)
+    );
+
+    // Dropping the pool lets queued jobs finish, then stops and joins every worker
+    let finished = Arc::new(AtomicUsize::new(0));
+    for _ in 0..50 {
+        let finished = Arc::clone(&finished);
+        pool.execute(move || {
+            finished.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    drop(pool);
+    println!("After dropping the pool: {} of 50 queued jobs finished", finished.load(Ordering::SeqCst));
+    println!();
+}
+
+fn word_count_examples() {
+    println!("5. Parallel Word Count (Map-Reduce)");
+
+    // Files named on the command line, or a made-up corpus if there are none
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    let documents: Vec<String> = if paths.is_empty() {
+        word_count::sample_corpus(16, 10_000)
+    } else {
+        paths
+            .iter()
+            .filter_map(|path| match std::fs::read_to_string(path) {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    println!("Skipping {}: {}", path, e);
+                    None
+                }
+            })
+            .collect()
+    };
+    let documents: Arc<[String]> = documents.into();
+    let workers = 4;
+
+    // Map: every worker counts the words of its share of the files.
+    // Reduce: the counts are merged. The designs differ in where merging happens.
+    let designs: [(&str, word_count::Design); 3] = [
+        ("channel reduction", word_count::with_channel),
+        ("shared Arc<Mutex<HashMap>>", word_count::with_shared_mutex),
+        ("sharded RwLock maps", word_count::with_sharded_rwlock),
+    ];
+    let mut results = Vec::new();
+    for (name, count) in designs {
+        let started = Instant::now();
+        let counts = count(Arc::clone(&documents), workers);
+        println!("  {:<28} {:?}", name, started.elapsed());
+        results.push(counts);
+    }
+    // Timings change from run to run and machine to machine; the counts must not
+    println!("All designs agree: {}", results.windows(2).all(|pair| pair[0] == pair[1]));
+
+    let counts = &results[0];
+    println!(
+        "{} files on {} workers: {} words, {} different",
+        documents.len(),
+        workers,
+        counts.values().sum::<usize>(),
+        counts.len()
+    );
+    println!("Most common: {:?}", word_count::most_common(counts, 5));
+    println!();
+}
+
+fn synchronization_examples() {
+    println!("6. Classic Synchronization Problems");
+
+    // Producers wait while the queue is full, consumers while it is empty
+    let queue = Arc::new(sync_problems::BoundedQueue::new(4));
+    let producers: Vec<_> = (0..3)
+        .map(|p| {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                for i in 0..10 {
+                    queue.push(p * 10 + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    let consumers: Vec<_> = (0..2)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                while let Some(item) = queue.pop() {
+                    received.push(item);
+                }
+                received
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    // Consumers empty the queue, then pop() returns None and they stop
+    queue.close();
+    let mut received: Vec<i32> = consumers.into_iter().flat_map(|consumer| consumer.join().unwrap()).collect();
+    received.sort();
+    println!("Bounded buffer: 3 producers sent 30 items to 2 consumers through 4 slots");
+    println!(
+        "  Received {} items, sum {}, never more than 4 queued: {}",
+        received.len(),
+        received.iter().sum::<i32>(),
+        queue.high_water_mark() <= 4
+    );
+
+    // No worker starts a round before all of them have finished the last one
+    let (rounds, in_step) = sync_problems::lockstep(4, 5);
+    println!("Barrier: 4 workers finished rounds {:?} in lockstep: {}", rounds, in_step);
+
+    let (count, torn) = sync_problems::readers_and_writers(4, 2, 10);
+    println!("Readers and writers: final count {} of 20, a reader saw a half-finished write: {}", count, torn);
+
+    // Every philosopher needs the forks on both sides to eat
+    let window = Duration::from_millis(200);
+    let (meals, report) = sync_problems::dining_philosophers(5, 3, true, window);
+    println!("Dining philosophers, lower-numbered fork first: {} meals of 15, watchdog quiet: {}", meals, report.is_none());
+
+    let (meals, report) = sync_problems::dining_philosophers(5, 3, false, window);
+    println!("Dining philosophers, left fork first: {} meals of 15", meals);
+    if let Some(report) = report {
+        println!("  Watchdog: no progress for {:?}. Who holds what:", window);
+        for line in report.to_string().lines() {
+            println!("    {}", line);
+        }
+    }
+    println!();
+}
+
+fn work_stealing_examples() {
+    println!("7. Work-Stealing Scheduler (Fork/Join)");
+
+    // The same made-up numbers every run, from a linear congruential generator
+    let mut state: u64 = 7;
+    let numbers: Vec<u64> = (0..500_000)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            state >> 32
+        })
+        .collect();
+    let mut expected = numbers.clone();
+    expected.sort();
+
+    // On a single core every design takes about as long as the sequential one
+    let threads = 4;
+    println!("Quicksort of {} numbers and fibonacci(30), on {} threads:", numbers.len(), threads);
+    // Outside a scheduler join() runs both halves itself, which makes the
+    // sequential version. The single queue is how ThreadPool above works.
+    let designs = [
+        ("sequential", None),
+        ("single shared queue", Some(Scheduler::single_queue(threads))),
+        ("work stealing", Some(Scheduler::work_stealing(threads))),
+    ];
+    let mut all_correct = true;
+    for (name, scheduler) in &designs {
+        let mut items = numbers.clone();
+        let started = Instant::now();
+        match scheduler {
+            Some(scheduler) => scheduler.run(|| work_stealing::quicksort(&mut items)),
+            None => work_stealing::quicksort(&mut items),
+        }
+        let sorting = started.elapsed();
+
+        let started = Instant::now();
+        let fibonacci = match scheduler {
+            Some(scheduler) => scheduler.run(|| work_stealing::fibonacci(30)),
+            None => work_stealing::fibonacci(30),
+        };
+        all_correct &= items == expected && fibonacci == 832_040;
+        println!("  {:<20} quicksort {:?}, fibonacci {:?}", name, sorting, started.elapsed());
+    }
+    println!("Every design sorted correctly and found fibonacci(30) = 832040: {}", all_correct);
+    println!();
+}
+
+fn lock_free_examples() {
+    println!("8. Below Mutex: Atomics and Memory Ordering");
+
+    // One producer, one consumer, no locks
+    let (mut producer, mut consumer) = lock_free::ring_buffer(64);
+    let sender = thread::spawn(move || {
+        for i in 0..100_000u64 {
+            let mut item = i;
+            // A full ring hands the item back; try again once the consumer caught up
+            while let Err(rejected) = producer.push(item) {
+                item = rejected;
+                thread::yield_now();
+            }
+        }
+    });
+    let (mut received, mut in_order, mut sum) = (0u64, true, 0u64);
+    while received < 100_000 {
+        match consumer.pop() {
+            Some(item) => {
+                in_order &= item == received;
+                sum += item;
+                received += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+    sender.join().unwrap();
+    println!("Ring buffer: {} numbers through 64 slots, in order: {}, sum {}", received, in_order, sum);
+
+    // Counters only need fetch_add to be atomic, not any ordering
+    let stats = Arc::new(lock_free::Stats::new());
+    let spin = Arc::new(lock_free::SpinLock::new(Vec::new()));
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let (stats, spin) = (Arc::clone(&stats), Arc::clone(&spin));
+            thread::spawn(move || {
+                for i in 0..1_000 {
+                    stats.record(t * 1_000 + i);
+                }
+                spin.with(|items| items.push(t));
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let (count, total, max) = stats.snapshot();
+    println!("Stats from 4 threads: {} samples, total {}, largest {}", count, total, max);
+    let mut finished = spin.with(|items| items.clone());
+    finished.sort();
+    println!("Spinlock: threads {:?} each added themselves once", finished);
+
+    // On x86 Relaxed happens to behave like Acquire/Release, so running this
+    // proves nothing either way. The model checker in the tests does.
+    println!("Why the ring needs Acquire/Release: cargo test --example 015-concurrency-threads lock_free");
+    println!();
+}
+
+mod thread_pool {
+    // A fixed number of worker threads share one channel of jobs. Each worker
+    // locks the receiving end just long enough to take a job, then runs it.
+
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    type Job = Box<dyn FnOnce() + Send + 'static>;
+
+    pub struct ThreadPool {
+        workers: Vec<Worker>,
+        // An Option so that Drop can close the channel before joining the workers
+        sender: Option<Sender<Job>>,
+        state: Arc<State>,
+    }
+
+    /// What the workers and the pool share besides the channel.
+    struct State {
+        /// Jobs submitted but not finished yet.
+        pending: Mutex<usize>,
+        /// Signalled when `pending` drops to zero.
+        idle: Condvar,
+        panicked: AtomicUsize,
+    }
+
+    struct Worker {
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl ThreadPool {
+        /// A pool of `size` threads. Panics if `size` is zero.
+        pub fn new(size: usize) -> ThreadPool {
+            assert!(size > 0, "a thread pool needs at least one thread");
+
+            let (sender, receiver) = mpsc::channel();
+            let receiver = Arc::new(Mutex::new(receiver));
+            let state = Arc::new(State { pending: Mutex::new(0), idle: Condvar::new(), panicked: AtomicUsize::new(0) });
+
+            let workers = (0..size).map(|id| Worker::new(id, Arc::clone(&receiver), Arc::clone(&state))).collect();
+            ThreadPool { workers, sender: Some(sender), state }
+        }
+
+        pub fn size(&self) -> usize {
+            self.workers.len()
+        }
+
+        /// Queues `job` to run on the first worker that is free.
+        pub fn execute<F>(&self, job: F)
+        where
+            F: FnOnce() + Send + 'static,
+        {
+            *self.state.pending.lock().unwrap() += 1;
+            self.sender.as_ref().unwrap().send(Box::new(job)).unwrap();
+        }
+
+        /// Waits until every job submitted so far has finished.
+        pub fn join(&self) {
+            let mut pending = self.state.pending.lock().unwrap();
+            while *pending > 0 {
+                pending = self.state.idle.wait(pending).unwrap();
+            }
+        }
+
+        /// How many jobs have panicked so far.
+        pub fn panicked(&self) -> usize {
+            self.state.panicked.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for ThreadPool {
+        /// Graceful shutdown: closing the channel lets the workers finish the
+        /// jobs already queued, after which `recv` fails and they stop.
+        fn drop(&mut self) {
+            drop(self.sender.take());
+            for worker in &mut self.workers {
+                if let Some(handle) = worker.handle.take() {
+                    handle.join().unwrap();
+                }
+            }
+        }
+    }
+
+    impl Worker {
+        fn new(id: usize, receiver: Arc<Mutex<Receiver<Job>>>, state: Arc<State>) -> Worker {
+            let handle = thread::Builder::new()
+                .name(format!("pool-worker-{}", id))
+                .spawn(move || loop {
+                    // The lock guard is a temporary, so it is released as soon as
+                    // recv() returns, before the job runs
+                    let message = receiver.lock().unwrap().recv();
+                    let Ok(job) = message else { break };
+
+                    // A panic unwinds only as far as this worker's loop
+                    if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                        state.panicked.fetch_add(1, Ordering::SeqCst);
+                    }
+
+                    let mut pending = state.pending.lock().unwrap();
+                    *pending -= 1;
+                    if *pending == 0 {
+                        state.idle.notify_all();
+                    }
+                })
+                .expect("failed to spawn a worker thread");
+            Worker { handle: Some(handle) }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::time::Duration;
+
+        #[test]
+        fn every_job_runs_exactly_once() {
+            let runs: Arc<Vec<AtomicUsize>> = Arc::new((0..1000).map(|_| AtomicUsize::new(0)).collect());
+            let pool = ThreadPool::new(4);
+            for i in 0..1000 {
+                let runs = Arc::clone(&runs);
+                pool.execute(move || {
+                    runs[i].fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            pool.join();
+            assert!(runs.iter().all(|count| count.load(Ordering::SeqCst) == 1));
+        }
+
+        #[test]
+        fn join_waits_and_the_pool_can_be_reused() {
+            let pool = ThreadPool::new(3);
+            let done = Arc::new(AtomicUsize::new(0));
+            for round in 1..=3 {
+                for _ in 0..10 {
+                    let done = Arc::clone(&done);
+                    pool.execute(move || {
+                        thread::sleep(Duration::from_millis(1));
+                        done.fetch_add(1, Ordering::SeqCst);
+                    });
+                }
+                pool.join();
+                assert_eq!(done.load(Ordering::SeqCst), round * 10);
+            }
+        }
+
+        #[test]
+        fn panicking_jobs_do_not_kill_workers() {
+            let pool = ThreadPool::new(2);
+            let done = Arc::new(AtomicUsize::new(0));
+            // More panics than workers: if a panic killed its worker, the
+            // jobs after them would never run and join() would hang
+            for _ in 0..10 {
+                pool.execute(|| panic!("job failed"));
+            }
+            for _ in 0..100 {
+                let done = Arc::clone(&done);
+                pool.execute(move || {
+                    done.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            pool.join();
+            assert_eq!(pool.panicked(), 10);
+            assert_eq!(done.load(Ordering::SeqCst), 100);
+        }
+
+        #[test]
+        fn dropping_the_pool_finishes_queued_jobs() {
+            let done = Arc::new(AtomicUsize::new(0));
+            let pool = ThreadPool::new(2);
+            for _ in 0..50 {
+                let done = Arc::clone(&done);
+                pool.execute(move || {
+                    thread::sleep(Duration::from_millis(1));
+                    done.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            drop(pool);
+            assert_eq!(done.load(Ordering::SeqCst), 50);
+        }
+
+        #[test]
+        #[should_panic(expected = "at least one thread")]
+        fn a_pool_needs_threads() {
+            ThreadPool::new(0);
+        }
+    }
+}
+
+mod word_count {
+    // Counting words in many files, split over worker threads. Each function
+    // gives every worker a share of the files; they differ in how the
+    // workers' counts end up in one map.
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::hash::{Hash, Hasher};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::sync::{Arc, Mutex, RwLock};
+    use std::thread;
+
+    pub type Counts = HashMap<String, usize>;
+
+    /// Counts the words in the documents using the given number of workers.
+    pub type Design = fn(Arc<[String]>, usize) -> Counts;
+
+    /// Lowercased words: runs of letters, digits and apostrophes.
+    pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .map(|word| word.trim_matches('\''))
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+    }
+
+    /// The single-threaded version, with the entry API from 008-collections.rs.
+    pub fn count(text: &str) -> Counts {
+        let mut counts = Counts::new();
+        for word in words(text) {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Runs `work` on `workers` threads, each with its own range of documents.
+    fn split<T, F>(documents: &Arc<[String]>, workers: usize, work: F) -> Vec<T>
+    where
+        T: Send + 'static,
+        F: Fn(&[String]) -> T + Send + Sync + 'static,
+    {
+        let share = documents.len().div_ceil(workers.max(1)).max(1);
+        let work = Arc::new(work);
+        let handles: Vec<_> = (0..documents.len())
+            .step_by(share)
+            .map(|start| {
+                let documents = Arc::clone(documents);
+                let work = Arc::clone(&work);
+                thread::spawn(move || {
+                    let end = (start + share).min(documents.len());
+                    work(&documents[start..end])
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    }
+
+    /// Every worker counts into a private map and sends it to the main thread,
+    /// which merges them. No locks at all; the merge happens on one thread.
+    pub fn with_channel(documents: Arc<[String]>, workers: usize) -> Counts {
+        let (sender, receiver) = mpsc::channel();
+        split(&documents, workers, move |share| {
+            let mut counts = Counts::new();
+            for document in share {
+                for word in words(document) {
+                    *counts.entry(word).or_insert(0) += 1;
+                }
+            }
+            sender.send(counts).unwrap();
+        });
+
+        let mut total = Counts::new();
+        for counts in receiver {
+            for (word, n) in counts {
+                *total.entry(word).or_insert(0) += n;
+            }
+        }
+        total
+    }
+
+    /// Every worker adds each word straight into one shared map. Simple, but
+    /// all the workers queue up for the same lock, once per word.
+    pub fn with_shared_mutex(documents: Arc<[String]>, workers: usize) -> Counts {
+        let total = Arc::new(Mutex::new(Counts::new()));
+        let shared = Arc::clone(&total);
+        split(&documents, workers, move |share| {
+            for document in share {
+                for word in words(document) {
+                    *shared.lock().unwrap().entry(word).or_insert(0) += 1;
+                }
+            }
+        });
+        // Every worker has been joined, so this is the only Arc left
+        Arc::try_unwrap(total).unwrap().into_inner().unwrap()
+    }
+
+    /// The map is cut into shards by the word's hash, each behind its own
+    /// RwLock. Words already in a shard only need a read lock, because their
+    /// counters are atomic; only new words take the write lock.
+    pub fn with_sharded_rwlock(documents: Arc<[String]>, workers: usize) -> Counts {
+        type Shard = RwLock<HashMap<String, AtomicUsize>>;
+        let shards: Arc<Vec<Shard>> = Arc::new((0..16).map(|_| RwLock::new(HashMap::new())).collect());
+        let shared = Arc::clone(&shards);
+        split(&documents, workers, move |share| {
+            for document in share {
+                for word in words(document) {
+                    let shard = &shared[shard_of(&word, shared.len())];
+                    if let Some(counter) = shard.read().unwrap().get(&word) {
+                        counter.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
+                    // Another worker may have inserted the word since the read; entry() copes
+                    shard.write().unwrap().entry(word).or_insert_with(|| AtomicUsize::new(0)).fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        let shards = Arc::try_unwrap(shards).unwrap();
+        shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap())
+            .map(|(word, counter)| (word, counter.into_inner()))
+            .collect()
+    }
+
+    fn shard_of(word: &str, shards: usize) -> usize {
+        let mut hasher = DefaultHasher::new();
+        word.hash(&mut hasher);
+        (hasher.finish() % shards as u64) as usize
+    }
+
+    /// The `n` most frequent words, ties broken alphabetically.
+    pub fn most_common(counts: &Counts, n: usize) -> Vec<(&str, usize)> {
+        let mut sorted: Vec<(&str, usize)> = counts.iter().map(|(word, n)| (word.as_str(), *n)).collect();
+        sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        sorted.truncate(n);
+        sorted
+    }
+
+    /// `files` made-up documents of `words` words each, the same on every run.
+    /// Words early in the list come up far more often, as in real text.
+    pub fn sample_corpus(files: usize, words: usize) -> Vec<String> {
+        const VOCABULARY: [&str; 24] = [
+            "the", "of", "and", "to", "a", "in", "is", "it", "you", "that", "thread", "lock", "channel", "rust",
+            "borrow", "owner", "mutex", "arc", "send", "sync", "worker", "pool", "shard", "atomic",
+        ];
+        // A small linear congruential generator: no dependencies, same numbers every time
+        let mut state: u64 = 42;
+        let mut next = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as f64 / (1u64 << 31) as f64
+        };
+        (0..files)
+            .map(|_| {
+                (0..words)
+                    .map(|_| {
+                        let u = next();
+                        VOCABULARY[(u * u * VOCABULARY.len() as f64) as usize]
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn documents() -> Arc<[String]> {
+            vec![
+                String::from("The pool's workers share the queue."),
+                String::from("Don't share a lock; share the WORK."),
+                String::new(),
+                String::from("the end"),
+            ]
+            .into()
+        }
+
+        #[test]
+        fn words_are_lowercased_and_split_on_punctuation() {
+            let words: Vec<String> = words("Don't stop -- 'quoted' words, OK?").collect();
+            assert_eq!(words, ["don't", "stop", "quoted", "words", "ok"]);
+        }
+
+        #[test]
+        fn every_design_matches_the_single_threaded_count() {
+            let expected = count(&documents().join("\n"));
+            assert_eq!(expected["the"], 4);
+            assert_eq!(expected["share"], 3);
+
+            for workers in [1, 2, 3, 8] {
+                assert_eq!(with_channel(documents(), workers), expected, "channel, {} workers", workers);
+                assert_eq!(with_shared_mutex(documents(), workers), expected, "mutex, {} workers", workers);
+                assert_eq!(with_sharded_rwlock(documents(), workers), expected, "rwlock, {} workers", workers);
+            }
+        }
+
+        #[test]
+        fn no_documents_means_no_words() {
+            let none: Arc<[String]> = Vec::new().into();
+            assert!(with_channel(Arc::clone(&none), 4).is_empty());
+            assert!(with_sharded_rwlock(none, 4).is_empty());
+        }
+
+        #[test]
+        fn the_sample_corpus_is_the_same_every_time() {
+            let corpus = sample_corpus(3, 500);
+            assert_eq!(corpus, sample_corpus(3, 500));
+            let counts = count(&corpus.join(" "));
+            assert_eq!(counts.values().sum::<usize>(), 1500);
+            assert_eq!(most_common(&counts, 1)[0].0, "the");
+        }
+    }
+}
+
+mod sync_problems {
+    // Classic synchronization problems, built from Mutex, Condvar and Barrier.
+    // The forks of the dining philosophers are TrackedLocks: they record who
+    // holds them, so a Watchdog can say who is stuck on whom when everything
+    // stops moving.
+
+    use std::cell::UnsafeCell;
+    use std::collections::{BTreeMap, VecDeque};
+    use std::fmt;
+    use std::ops::{Deref, DerefMut};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier, Condvar, Mutex};
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    /// A queue of at most `capacity` items: `push` waits while it is full
+    /// and `pop` waits while it is empty.
+    pub struct BoundedQueue<T> {
+        state: Mutex<QueueState<T>>,
+        not_full: Condvar,
+        not_empty: Condvar,
+        capacity: usize,
+    }
+
+    struct QueueState<T> {
+        items: VecDeque<T>,
+        closed: bool,
+        /// The most items that were ever queued at once.
+        high_water_mark: usize,
+    }
+
+    impl<T> BoundedQueue<T> {
+        /// Panics if `capacity` is zero.
+        pub fn new(capacity: usize) -> BoundedQueue<T> {
+            assert!(capacity > 0, "a bounded queue needs room for at least one item");
+            let state = QueueState { items: VecDeque::with_capacity(capacity), closed: false, high_water_mark: 0 };
+            BoundedQueue { state: Mutex::new(state), not_full: Condvar::new(), not_empty: Condvar::new(), capacity }
+        }
+
+        /// Waits for a free slot, then adds `item`. Hands it back if the
+        /// queue has been closed.
+        pub fn push(&self, item: T) -> Result<(), T> {
+            let mut state = self.state.lock().unwrap();
+            // `while`, not `if`: wakeups can be spurious, and another producer
+            // may have taken the free slot first
+            while state.items.len() == self.capacity && !state.closed {
+                state = self.not_full.wait(state).unwrap();
+            }
+            if state.closed {
+                return Err(item);
+            }
+            state.items.push_back(item);
+            state.high_water_mark = state.high_water_mark.max(state.items.len());
+            self.not_empty.notify_one();
+            Ok(())
+        }
+
+        /// Waits for an item. `None` once the queue is closed and empty.
+        pub fn pop(&self) -> Option<T> {
+            let mut state = self.state.lock().unwrap();
+            loop {
+                if let Some(item) = state.items.pop_front() {
+                    self.not_full.notify_one();
+                    return Some(item);
+                }
+                if state.closed {
+                    return None;
+                }
+                state = self.not_empty.wait(state).unwrap();
+            }
+        }
+
+        /// No more items will come: consumers get what is left, then `None`.
+        pub fn close(&self) {
+            self.state.lock().unwrap().closed = true;
+            self.not_full.notify_all();
+            self.not_empty.notify_all();
+        }
+
+        pub fn high_water_mark(&self) -> usize {
+            self.state.lock().unwrap().high_water_mark
+        }
+    }
+
+    /// `workers` threads do `rounds` rounds of work. A Barrier between rounds
+    /// keeps them in step; returns the rounds in the order they were finished
+    /// and whether every worker saw all the others done with each round.
+    pub fn lockstep(workers: usize, rounds: usize) -> (Vec<usize>, bool) {
+        let barrier = Arc::new(Barrier::new(workers));
+        let done: Arc<Vec<AtomicUsize>> = Arc::new((0..workers).map(|_| AtomicUsize::new(usize::MAX)).collect());
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let in_step = Arc::new(AtomicBool::new(true));
+
+        let handles: Vec<_> = (0..workers)
+            .map(|id| {
+                let (barrier, done, finished, in_step) =
+                    (Arc::clone(&barrier), Arc::clone(&done), Arc::clone(&finished), Arc::clone(&in_step));
+                thread::spawn(move || {
+                    for round in 0..rounds {
+                        // "The work" of this round
+                        done[id].store(round, Ordering::SeqCst);
+
+                        barrier.wait();
+                        if done.iter().any(|slot| slot.load(Ordering::SeqCst) != round) {
+                            in_step.store(false, Ordering::SeqCst);
+                        }
+                        // Wait again so nobody starts the next round while
+                        // others are still checking this one. One thread is
+                        // told it is the leader; it does the bookkeeping.
+                        if barrier.wait().is_leader() {
+                            finished.lock().unwrap().push(round);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let finished = finished.lock().unwrap().clone();
+        (finished, in_step.load(Ordering::SeqCst))
+    }
+
+    /// A readers-writer lock from a Mutex and a Condvar, a simple version of
+    /// std::sync::RwLock. Any number of readers, or one writer. Writers go
+    /// first: once one is waiting, new readers wait too, so a steady stream
+    /// of readers cannot starve it.
+    pub struct ReadWriteLock<T> {
+        state: Mutex<Access>,
+        changed: Condvar,
+        value: UnsafeCell<T>,
+    }
+
+    #[derive(Default)]
+    struct Access {
+        readers: usize,
+        writing: bool,
+        writers_waiting: usize,
+    }
+
+    // SAFETY: `state` makes sure `value` is only ever shared by readers or
+    // used by a single writer, which is what RwLock promises too
+    unsafe impl<T: Send + Sync> Sync for ReadWriteLock<T> {}
+
+    pub struct ReadGuard<'a, T> {
+        lock: &'a ReadWriteLock<T>,
+    }
+
+    pub struct WriteGuard<'a, T> {
+        lock: &'a ReadWriteLock<T>,
+    }
+
+    impl<T> ReadWriteLock<T> {
+        pub fn new(value: T) -> ReadWriteLock<T> {
+            ReadWriteLock { state: Mutex::new(Access::default()), changed: Condvar::new(), value: UnsafeCell::new(value) }
+        }
+
+        pub fn read(&self) -> ReadGuard<'_, T> {
+            let mut state = self.state.lock().unwrap();
+            while state.writing || state.writers_waiting > 0 {
+                state = self.changed.wait(state).unwrap();
+            }
+            state.readers += 1;
+            ReadGuard { lock: self }
+        }
+
+        pub fn write(&self) -> WriteGuard<'_, T> {
+            let mut state = self.state.lock().unwrap();
+            state.writers_waiting += 1;
+            while state.writing || state.readers > 0 {
+                state = self.changed.wait(state).unwrap();
+            }
+            state.writers_waiting -= 1;
+            state.writing = true;
+            WriteGuard { lock: self }
+        }
+    }
+
+    impl<T> Deref for ReadGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            // SAFETY: while this guard lives, `readers` is above zero, so no writer gets in
+            unsafe { &*self.lock.value.get() }
+        }
+    }
+
+    impl<T> Drop for ReadGuard<'_, T> {
+        fn drop(&mut self) {
+            let mut state = self.lock.state.lock().unwrap();
+            state.readers -= 1;
+            if state.readers == 0 {
+                self.lock.changed.notify_all();
+            }
+        }
+    }
+
+    impl<T> Deref for WriteGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            // SAFETY: while this guard lives, `writing` is set, so nobody else gets in
+            unsafe { &*self.lock.value.get() }
+        }
+    }
+
+    impl<T> DerefMut for WriteGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            // SAFETY: as above, and `&mut self` means this guard hands out one reference at a time
+            unsafe { &mut *self.lock.value.get() }
+        }
+    }
+
+    impl<T> Drop for WriteGuard<'_, T> {
+        fn drop(&mut self) {
+            self.lock.state.lock().unwrap().writing = false;
+            // Both waiting readers and waiting writers may go now
+            self.lock.changed.notify_all();
+        }
+    }
+
+    /// Writers bump both halves of a pair, one at a time; readers check the
+    /// halves match. Returns the final count and whether any reader ever saw
+    /// a write half done, which the lock should make impossible.
+    pub fn readers_and_writers(readers: usize, writers: usize, writes: usize) -> (usize, bool) {
+        let pair = Arc::new(ReadWriteLock::new((0, 0)));
+        let torn = Arc::new(AtomicBool::new(false));
+
+        let mut handles = Vec::new();
+        for _ in 0..writers {
+            let pair = Arc::clone(&pair);
+            handles.push(thread::spawn(move || {
+                for _ in 0..writes {
+                    let mut pair = pair.write();
+                    pair.0 += 1;
+                    // Give readers every chance to look at the half-done write
+                    thread::yield_now();
+                    pair.1 += 1;
+                }
+            }));
+        }
+        for _ in 0..readers {
+            let (pair, torn) = (Arc::clone(&pair), Arc::clone(&torn));
+            handles.push(thread::spawn(move || {
+                for _ in 0..writes * 5 {
+                    let pair = pair.read();
+                    if pair.0 != pair.1 {
+                        torn.store(true, Ordering::SeqCst);
+                    }
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let count = pair.read().0;
+        (count, torn.load(Ordering::SeqCst))
+    }
+
+    /// Who holds which TrackedLock and who is waiting for which, plus a
+    /// counter of work done: everything the Watchdog looks at.
+    #[derive(Default)]
+    pub struct Monitor {
+        /// Lock name to the name of the thread holding it.
+        holders: Mutex<BTreeMap<String, String>>,
+        /// Thread name to the name of the lock it is waiting for.
+        waiting: Mutex<BTreeMap<String, String>>,
+        progress: AtomicUsize,
+        gave_up: AtomicBool,
+    }
+
+    impl Monitor {
+        pub fn new() -> Arc<Monitor> {
+            Arc::new(Monitor::default())
+        }
+
+        /// Threads call this whenever they get something done.
+        pub fn progress(&self) {
+            self.progress.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn report(&self) -> Report {
+            Report { held: self.holders.lock().unwrap().clone(), waiting: self.waiting.lock().unwrap().clone() }
+        }
+    }
+
+    /// A Mutex without data that remembers which thread holds it. Locking
+    /// fails once the Watchdog has declared a deadlock, because a thread stuck
+    /// in std's Mutex::lock can never be woken.
+    pub struct TrackedLock {
+        name: String,
+        holder: Mutex<Option<String>>,
+        released: Condvar,
+        monitor: Arc<Monitor>,
+    }
+
+    pub struct TrackedGuard<'a> {
+        lock: &'a TrackedLock,
+    }
+
+    /// The Watchdog declared a deadlock while this thread was waiting.
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct GaveUp;
+
+    impl TrackedLock {
+        pub fn new(name: &str, monitor: &Arc<Monitor>) -> TrackedLock {
+            TrackedLock {
+                name: name.to_string(),
+                holder: Mutex::new(None),
+                released: Condvar::new(),
+                monitor: Arc::clone(monitor),
+            }
+        }
+
+        pub fn lock(&self) -> Result<TrackedGuard<'_>, GaveUp> {
+            let me = thread::current().name().unwrap_or("unnamed").to_string();
+            let mut holder = self.holder.lock().unwrap();
+            if holder.is_some() {
+                self.monitor.waiting.lock().unwrap().insert(me.clone(), self.name.clone());
+            }
+            loop {
+                // Checked even when the lock is free: after a deadlock nobody
+                // should carry on as if nothing happened
+                if self.monitor.gave_up.load(Ordering::SeqCst) {
+                    self.monitor.waiting.lock().unwrap().remove(&me);
+                    return Err(GaveUp);
+                }
+                if holder.is_none() {
+                    break;
+                }
+                // Wake up now and then to see whether the watchdog gave up
+                holder = self.released.wait_timeout(holder, Duration::from_millis(10)).unwrap().0;
+            }
+            self.monitor.waiting.lock().unwrap().remove(&me);
+            self.monitor.holders.lock().unwrap().insert(self.name.clone(), me.clone());
+            *holder = Some(me);
+            Ok(TrackedGuard { lock: self })
+        }
+    }
+
+    impl Drop for TrackedGuard<'_> {
+        fn drop(&mut self) {
+            let mut holder = self.lock.holder.lock().unwrap();
+            *holder = None;
+            self.lock.monitor.holders.lock().unwrap().remove(&self.lock.name);
+            self.lock.released.notify_one();
+        }
+    }
+
+    /// What the Watchdog saw when it gave up.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Report {
+        /// Lock name to thread name.
+        pub held: BTreeMap<String, String>,
+        /// Thread name to lock name.
+        pub waiting: BTreeMap<String, String>,
+    }
+
+    impl fmt::Display for Report {
+        /// One line per thread: "philosopher-0 holds fork-0, waits for fork-1".
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let mut threads: BTreeMap<&str, (Vec<&str>, Option<&str>)> = BTreeMap::new();
+            for (lock, thread) in &self.held {
+                threads.entry(thread).or_default().0.push(lock);
+            }
+            for (thread, lock) in &self.waiting {
+                threads.entry(thread).or_default().1 = Some(lock);
+            }
+            for (thread, (held, waiting)) in threads {
+                write!(f, "{}", thread)?;
+                if !held.is_empty() {
+                    write!(f, " holds {}", held.join(", "))?;
+                }
+                if let Some(lock) = waiting {
+                    write!(f, "{} waits for {}", if held.is_empty() { "" } else { "," }, lock)?;
+                }
+                writeln!(f)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// A thread that watches a Monitor's progress counter. If it does not
+    /// move for a whole `window`, the watchdog takes a Report and tells every
+    /// TrackedLock to stop waiting.
+    pub struct Watchdog {
+        finished: Arc<AtomicBool>,
+        handle: JoinHandle<Option<Report>>,
+    }
+
+    impl Watchdog {
+        pub fn spawn(monitor: Arc<Monitor>, window: Duration) -> Watchdog {
+            let finished = Arc::new(AtomicBool::new(false));
+            let watching = Arc::clone(&finished);
+            let handle = thread::spawn(move || {
+                let mut last = monitor.progress.load(Ordering::SeqCst);
+                let mut since = Instant::now();
+                while !watching.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(10));
+                    let now = monitor.progress.load(Ordering::SeqCst);
+                    if now != last {
+                        last = now;
+                        since = Instant::now();
+                    } else if since.elapsed() >= window {
+                        // The report first: giving up makes the waiters leave
+                        let report = monitor.report();
+                        monitor.gave_up.store(true, Ordering::SeqCst);
+                        return Some(report);
+                    }
+                }
+                None
+            });
+            Watchdog { finished, handle }
+        }
+
+        /// Stops watching. The report, if the watchdog had to step in.
+        pub fn finish(self) -> Option<Report> {
+            self.finished.store(true, Ordering::SeqCst);
+            self.handle.join().unwrap()
+        }
+    }
+
+    /// `count` philosophers around a table with a fork between each pair,
+    /// each wanting `meals` meals. Philosopher i needs forks i and i + 1.
+    ///
+    /// Taking the left fork first deadlocks as soon as everyone holds one;
+    /// a Barrier makes that unlucky moment certain here instead of rare.
+    /// Taking the lower-numbered fork first (`ordered`) puts the locks in
+    /// one global order, so a cycle of waiting threads cannot form.
+    ///
+    /// Returns the meals eaten and the watchdog's report if it had to step in.
+    pub fn dining_philosophers(count: usize, meals: usize, ordered: bool, window: Duration) -> (usize, Option<Report>) {
+        let monitor = Monitor::new();
+        let forks: Arc<Vec<TrackedLock>> =
+            Arc::new((0..count).map(|i| TrackedLock::new(&format!("fork-{}", i), &monitor)).collect());
+        let everyone_has_a_fork = (!ordered).then(|| Arc::new(Barrier::new(count)));
+        let watchdog = Watchdog::spawn(Arc::clone(&monitor), window);
+
+        let philosophers: Vec<_> = (0..count)
+            .map(|i| {
+                let (forks, monitor, barrier) = (Arc::clone(&forks), Arc::clone(&monitor), everyone_has_a_fork.clone());
+                let (left, right) = (i, (i + 1) % count);
+                let (first, second) = if ordered { (left.min(right), left.max(right)) } else { (left, right) };
+                thread::Builder::new()
+                    .name(format!("philosopher-{}", i))
+                    .spawn(move || {
+                        let mut eaten = 0;
+                        for meal in 0..meals {
+                            let Ok(_first) = forks[first].lock() else { break };
+                            if let (0, Some(barrier)) = (meal, &barrier) {
+                                barrier.wait();
+                            }
+                            let Ok(_second) = forks[second].lock() else { break };
+                            eaten += 1;
+                            monitor.progress();
+                            thread::sleep(Duration::from_millis(1));
+                        }
+                        eaten
+                    })
+                    .expect("failed to spawn a philosopher")
+            })
+            .collect();
+
+        let meals = philosophers.into_iter().map(|philosopher| philosopher.join().unwrap()).sum();
+        (meals, watchdog.finish())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn the_queue_stays_within_capacity_and_drains_after_close() {
+            let queue = Arc::new(BoundedQueue::new(2));
+            let producer = {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    for i in 1..=100 {
+                        queue.push(i).unwrap();
+                    }
+                    queue.close();
+                })
+            };
+            let mut received = Vec::new();
+            while let Some(item) = queue.pop() {
+                received.push(item);
+            }
+            producer.join().unwrap();
+
+            // One consumer, one producer: the order is kept too
+            assert_eq!(received, (1..=100).collect::<Vec<_>>());
+            assert!(queue.high_water_mark() <= 2);
+            assert_eq!(queue.push(101), Err(101));
+        }
+
+        #[test]
+        fn pop_waits_for_a_push() {
+            let queue = Arc::new(BoundedQueue::new(1));
+            let consumer = {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || queue.pop())
+            };
+            thread::sleep(Duration::from_millis(20));
+            queue.push("late").unwrap();
+            assert_eq!(consumer.join().unwrap(), Some("late"));
+        }
+
+        #[test]
+        fn barrier_rounds_finish_in_order() {
+            assert_eq!(lockstep(3, 4), (vec![0, 1, 2, 3], true));
+        }
+
+        #[test]
+        fn readers_share_and_writers_wait_for_them() {
+            let lock = Arc::new(ReadWriteLock::new(0));
+            let first = lock.read();
+            let second = lock.read();
+            assert_eq!(*first + *second, 0);
+
+            let writer = {
+                let lock = Arc::clone(&lock);
+                thread::spawn(move || *lock.write() = 1)
+            };
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(*first, 0, "the writer got in while readers were inside");
+            drop((first, second));
+            writer.join().unwrap();
+            assert_eq!(*lock.read(), 1);
+
+            assert_eq!(readers_and_writers(3, 3, 20), (60, false));
+        }
+
+        #[test]
+        fn ordered_forks_feed_everyone() {
+            assert_eq!(dining_philosophers(5, 4, true, Duration::from_millis(500)), (20, None));
+        }
+
+        #[test]
+        fn the_watchdog_reports_the_deadlock() {
+            let (meals, report) = dining_philosophers(3, 2, false, Duration::from_millis(100));
+            assert_eq!(meals, 0);
+            assert_eq!(
+                report.unwrap().to_string(),
+                "philosopher-0 holds fork-0, waits for fork-1\n\
+                 philosopher-1 holds fork-1, waits for fork-2\n\
+                 philosopher-2 holds fork-2, waits for fork-0\n"
+            );
+        }
+
+        #[test]
+        fn the_watchdog_stays_quiet_while_work_gets_done() {
+            let monitor = Monitor::new();
+            let watchdog = Watchdog::spawn(Arc::clone(&monitor), Duration::from_millis(100));
+            for _ in 0..10 {
+                thread::sleep(Duration::from_millis(15));
+                monitor.progress();
+            }
+            assert_eq!(watchdog.finish(), None);
+        }
+    }
+}
+
+mod work_stealing {
+    // A fork/join scheduler. Every worker has its own VecDeque of tasks: it
+    // pushes and pops at the back, like a stack, while idle workers steal
+    // from the front of someone else's, where the oldest and usually biggest
+    // tasks are. Work from outside the pool comes in through a global queue,
+    // the injector. Compared with ThreadPool's single queue, workers mostly
+    // touch their own deque, so they rarely wait for the same lock.
+
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+
+    type Job = Box<dyn FnOnce() + Send + 'static>;
+
+    pub struct Scheduler {
+        shared: Arc<Shared>,
+        threads: Vec<JoinHandle<()>>,
+    }
+
+    struct Shared {
+        injector: Mutex<VecDeque<Job>>,
+        /// One deque per worker; none at all in the single-queue design.
+        locals: Vec<Mutex<VecDeque<Job>>>,
+        /// Idle workers nap on `wake`; new work wakes one of them.
+        idle: Mutex<()>,
+        wake: Condvar,
+        shutdown: AtomicBool,
+        steals: AtomicUsize,
+    }
+
+    thread_local! {
+        /// The scheduler this thread works for and its index, if it is a worker.
+        static WORKER: RefCell<Option<(Arc<Shared>, usize)>> = const { RefCell::new(None) };
+        /// State of the random number generator that picks victims.
+        static SEED: Cell<u64> = const { Cell::new(1) };
+    }
+
+    impl Scheduler {
+        /// `threads` workers, each with its own deque. Panics if `threads` is zero.
+        pub fn work_stealing(threads: usize) -> Scheduler {
+            Scheduler::new(threads, true)
+        }
+
+        /// `threads` workers that share the injector and nothing else.
+        pub fn single_queue(threads: usize) -> Scheduler {
+            Scheduler::new(threads, false)
+        }
+
+        fn new(threads: usize, stealing: bool) -> Scheduler {
+            assert!(threads > 0, "a scheduler needs at least one thread");
+            let locals = if stealing { (0..threads).map(|_| Mutex::new(VecDeque::new())).collect() } else { Vec::new() };
+            let shared = Arc::new(Shared {
+                injector: Mutex::new(VecDeque::new()),
+                locals,
+                idle: Mutex::new(()),
+                wake: Condvar::new(),
+                shutdown: AtomicBool::new(false),
+                steals: AtomicUsize::new(0),
+            });
+
+            let threads = (0..threads)
+                .map(|index| {
+                    let shared = Arc::clone(&shared);
+                    thread::Builder::new()
+                        .name(format!("stealer-{}", index))
+                        // Waiting in join() runs other tasks on top of the
+                        // current one, so stacks get deeper than usual
+                        .stack_size(16 * 1024 * 1024)
+                        .spawn(move || work(shared, index))
+                        .expect("failed to spawn a worker thread")
+                })
+                .collect();
+            Scheduler { shared, threads }
+        }
+
+        /// Runs `f` on one of the workers and waits for its result. Calls to
+        /// `join` inside `f` spread the work over the whole pool.
+        pub fn run<R, F>(&self, f: F) -> R
+        where
+            F: FnOnce() -> R + Send,
+            R: Send,
+        {
+            let slot = Arc::new(Slot::new());
+            // SAFETY: we wait for the job below, so whatever `f` borrows outlives it
+            self.shared.push(unsafe { erase(slot_job(f, &slot)) }, None);
+            match slot.wait() {
+                Ok(result) => result,
+                Err(panic) => panic::resume_unwind(panic),
+            }
+        }
+
+        /// How many tasks were stolen so far.
+        pub fn steals(&self) -> usize {
+            self.shared.steals.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for Scheduler {
+        fn drop(&mut self) {
+            self.shared.shutdown.store(true, Ordering::SeqCst);
+            self.shared.wake.notify_all();
+            for thread in self.threads.drain(..) {
+                thread.join().unwrap();
+            }
+        }
+    }
+
+    impl Shared {
+        /// Onto `worker`'s own deque if it has one, otherwise onto the injector.
+        fn push(&self, job: Job, worker: Option<usize>) {
+            match worker.and_then(|index| self.locals.get(index)) {
+                Some(local) => local.lock().unwrap().push_back(job),
+                None => self.injector.lock().unwrap().push_back(job),
+            }
+            self.wake.notify_one();
+        }
+
+        /// The next job for worker `index`: its own newest, then the
+        /// injector's oldest, then one stolen from someone else.
+        fn find_work(&self, index: usize) -> Option<Job> {
+            if let Some(job) = self.locals.get(index).and_then(|local| local.lock().unwrap().pop_back()) {
+                return Some(job);
+            }
+            if let Some(job) = self.injector.lock().unwrap().pop_front() {
+                return Some(job);
+            }
+            self.steal(index)
+        }
+
+        /// Tries every other deque once, starting from a random one so that
+        /// thieves spread out instead of all robbing worker 0.
+        fn steal(&self, thief: usize) -> Option<Job> {
+            let count = self.locals.len();
+            if count < 2 {
+                return None;
+            }
+            let start = random() as usize % count;
+            for offset in 0..count {
+                let victim = (start + offset) % count;
+                if victim == thief {
+                    continue;
+                }
+                // The front holds the oldest task, which has the most work left to split
+                let stolen = self.locals[victim].lock().unwrap().pop_front();
+                if stolen.is_some() {
+                    self.steals.fetch_add(1, Ordering::Relaxed);
+                    return stolen;
+                }
+            }
+            None
+        }
+    }
+
+    fn work(shared: Arc<Shared>, index: usize) {
+        WORKER.with(|worker| *worker.borrow_mut() = Some((Arc::clone(&shared), index)));
+        SEED.with(|seed| seed.set((index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+
+        while !shared.shutdown.load(Ordering::SeqCst) {
+            match shared.find_work(index) {
+                // Jobs catch their own panics, see slot_job()
+                Some(job) => job(),
+                None => {
+                    // The timeout covers work pushed between looking and napping
+                    let idle = shared.idle.lock().unwrap();
+                    drop(shared.wake.wait_timeout(idle, Duration::from_millis(1)).unwrap());
+                }
+            }
+        }
+    }
+
+    /// xorshift64: plenty random enough to pick a victim.
+    fn random() -> u64 {
+        SEED.with(|seed| {
+            let mut x = seed.get();
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            seed.set(x);
+            x
+        })
+    }
+
+    /// Where a task leaves its result, or its panic.
+    struct Slot<R> {
+        result: Mutex<Option<thread::Result<R>>>,
+        ready: Condvar,
+    }
+
+    impl<R> Slot<R> {
+        fn new() -> Slot<R> {
+            Slot { result: Mutex::new(None), ready: Condvar::new() }
+        }
+
+        fn is_ready(&self) -> bool {
+            self.result.lock().unwrap().is_some()
+        }
+
+        fn wait(&self) -> thread::Result<R> {
+            let mut result = self.result.lock().unwrap();
+            loop {
+                if let Some(result) = result.take() {
+                    return result;
+                }
+                result = self.ready.wait(result).unwrap();
+            }
+        }
+    }
+
+    /// A job that runs `f` and puts what happened into `slot`.
+    fn slot_job<'a, R, F>(f: F, slot: &Arc<Slot<R>>) -> Box<dyn FnOnce() + Send + 'a>
+    where
+        F: FnOnce() -> R + Send + 'a,
+        R: Send + 'a,
+    {
+        let slot = Arc::clone(slot);
+        Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            *slot.result.lock().unwrap() = Some(result);
+            slot.ready.notify_all();
+        })
+    }
+
+    /// Lets a job that borrows from the caller's stack go into a queue,
+    /// where jobs have to be 'static. This is how fork/join can work on
+    /// `&mut` slices, as std::thread::scope does.
+    ///
+    /// # Safety
+    ///
+    /// The caller must wait for the job to finish before anything it
+    /// borrows goes away, even when unwinding.
+    unsafe fn erase<'a>(job: Box<dyn FnOnce() + Send + 'a>) -> Job {
+        unsafe { std::mem::transmute::<Box<dyn FnOnce() + Send + 'a>, Job>(job) }
+    }
+
+    /// Runs `a` and `b`, in parallel if a worker is free, and returns both
+    /// results. `b` waits in this worker's deque for a thief while this
+    /// thread runs `a`; if nobody took it, this thread runs it next.
+    /// Outside a scheduler both simply run on the calling thread.
+    pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send,
+    {
+        let Some((shared, index)) = WORKER.with(|worker| worker.borrow().clone()) else {
+            return (a(), b());
+        };
+        let slot = Arc::new(Slot::new());
+        // SAFETY: we do not return, or unwind, until `slot` is filled: see below
+        shared.push(unsafe { erase(slot_job(b, &slot)) }, Some(index));
+
+        // A panic in `a` has to wait for `b` as well, since `b` may borrow from this stack frame
+        let result_a = panic::catch_unwind(AssertUnwindSafe(a));
+        while !slot.is_ready() {
+            // Rather than sit idle, help out. Unless it was stolen, `b` is
+            // still at the back of our own deque, so it usually runs next.
+            match shared.find_work(index) {
+                Some(job) => job(),
+                None => thread::yield_now(),
+            }
+        }
+        match (result_a, slot.wait()) {
+            (Ok(a), Ok(b)) => (a, b),
+            (Err(panic), _) | (_, Err(panic)) => panic::resume_unwind(panic),
+        }
+    }
+
+    /// Splits around a pivot, then sorts both sides with join().
+    pub fn quicksort<T: Ord + Send>(items: &mut [T]) {
+        // Below this, handing out tasks costs more than it saves
+        if items.len() <= 2_000 {
+            items.sort_unstable();
+            return;
+        }
+        let pivot = partition(items);
+        let (left, right) = items.split_at_mut(pivot);
+        join(|| quicksort(left), || quicksort(&mut right[1..]));
+    }
+
+    /// Lomuto partition around the median of the first, middle and last
+    /// items. Returns where the pivot ends up.
+    fn partition<T: Ord>(items: &mut [T]) -> usize {
+        let (middle, last) = (items.len() / 2, items.len() - 1);
+        if items[middle] < items[0] {
+            items.swap(middle, 0);
+        }
+        if items[last] < items[0] {
+            items.swap(last, 0);
+        }
+        if items[last] < items[middle] {
+            items.swap(last, middle);
+        }
+        // The median is in the middle now; park it at the end
+        items.swap(middle, last);
+
+        let mut store = 0;
+        for i in 0..last {
+            if items[i] < items[last] {
+                items.swap(i, store);
+                store += 1;
+            }
+        }
+        items.swap(store, last);
+        store
+    }
+
+    /// The slow, doubly recursive Fibonacci: a lot of small tasks.
+    pub fn fibonacci(n: u64) -> u64 {
+        if n < 20 {
+            return sequential_fibonacci(n);
+        }
+        let (a, b) = join(|| fibonacci(n - 1), || fibonacci(n - 2));
+        a + b
+    }
+
+    fn sequential_fibonacci(n: u64) -> u64 {
+        if n < 2 { n } else { sequential_fibonacci(n - 1) + sequential_fibonacci(n - 2) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn numbers(count: usize) -> Vec<u32> {
+            // Lots of repeats, to exercise the partition
+            (0..count as u32).map(|i| i.wrapping_mul(2_654_435_761) % 1_000).collect()
+        }
+
+        #[test]
+        fn join_works_inside_and_outside_a_scheduler() {
+            assert_eq!(join(|| 1, || "two"), (1, "two"));
+            let scheduler = Scheduler::work_stealing(2);
+            assert_eq!(scheduler.run(|| join(|| 1, || "two")), (1, "two"));
+        }
+
+        #[test]
+        fn both_designs_sort_and_add_up() {
+            for scheduler in [Scheduler::single_queue(3), Scheduler::work_stealing(3)] {
+                for count in [0, 1, 2_001, 50_000] {
+                    let mut items = numbers(count);
+                    let mut expected = items.clone();
+                    expected.sort();
+                    scheduler.run(|| quicksort(&mut items));
+                    assert_eq!(items, expected, "{} items", count);
+                }
+                assert_eq!(scheduler.run(|| fibonacci(25)), 75_025);
+            }
+        }
+
+        #[test]
+        fn idle_workers_steal() {
+            let scheduler = Scheduler::work_stealing(2);
+            let started = AtomicBool::new(false);
+            // `a` keeps its own thread busy until `b` starts, so `b` has to be stolen
+            let (waited, ()) = scheduler.run(|| {
+                join(
+                    || {
+                        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+                        while !started.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+                            thread::yield_now();
+                        }
+                        started.load(Ordering::SeqCst)
+                    },
+                    || started.store(true, Ordering::SeqCst),
+                )
+            });
+            assert!(waited);
+            assert_eq!(scheduler.steals(), 1);
+        }
+
+        #[test]
+        fn panics_reach_the_caller_and_the_workers_survive() {
+            let scheduler = Scheduler::work_stealing(2);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                scheduler.run(|| join(|| 1, || -> i32 { panic!("task failed") }))
+            }));
+            assert!(result.is_err());
+            assert_eq!(scheduler.run(|| fibonacci(22)), 17_711);
+        }
+
+        #[test]
+        #[should_panic(expected = "at least one thread")]
+        fn a_scheduler_needs_threads() {
+            Scheduler::work_stealing(0);
+        }
+    }
+}
+
+mod lock_free {
+    // Lock-free code talks through atomics. Each atomic is always consistent
+    // on its own; the Ordering says what else a thread can rely on:
+    // - Relaxed: nothing beyond the atomic itself.
+    // - Release (on a store) with Acquire (on a load that sees that store):
+    //   everything written before the store is visible after the load.
+    //
+    // Under `cargo test` the atomics, cells and Arc below come from loom, a
+    // model checker that runs each test under every interleaving of its
+    // threads and every value a load is allowed to see. Outside tests they
+    // are std's, so the example itself needs nothing but std.
+
+    use std::mem::MaybeUninit;
+
+    #[cfg(not(test))]
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    #[cfg(not(test))]
+    use std::sync::Arc;
+
+    #[cfg(test)]
+    use loom::cell::UnsafeCell;
+    #[cfg(test)]
+    use loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    #[cfg(test)]
+    use loom::sync::Arc;
+
+    /// std's UnsafeCell behind loom's interface: every access goes through a
+    /// closure, which under loom lets it check who touched the value when.
+    #[cfg(not(test))]
+    struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+    #[cfg(not(test))]
+    impl<T> UnsafeCell<T> {
+        fn new(value: T) -> UnsafeCell<T> {
+            UnsafeCell(std::cell::UnsafeCell::new(value))
+        }
+
+        fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+            f(self.0.get())
+        }
+
+        fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+            f(self.0.get())
+        }
+    }
+
+    /// Tells the CPU we are busy-waiting. Under loom it lets the other
+    /// threads run, or the model would spin forever.
+    fn spin() {
+        #[cfg(not(test))]
+        std::hint::spin_loop();
+        #[cfg(test)]
+        loom::thread::yield_now();
+    }
+
+    /// A fixed-size queue for exactly one producer and one consumer. The
+    /// producer only writes `tail`, the consumer only writes `head`, so
+    /// neither needs a lock.
+    struct Ring<T> {
+        slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+        /// How many items were ever popped. Only the consumer stores it.
+        head: AtomicUsize,
+        /// How many items were ever pushed. Only the producer stores it.
+        tail: AtomicUsize,
+        /// Use Relaxed everywhere, which is wrong; for the tests.
+        relaxed: bool,
+    }
+
+    // SAFETY: a slot is only written by the producer while the consumer
+    // cannot reach it and read by the consumer while the producer cannot;
+    // the Acquire/Release pairs make sure each side sees the other's writes
+    unsafe impl<T: Send> Sync for Ring<T> {}
+
+    impl<T> Ring<T> {
+        /// For the store that hands a slot over to the other side.
+        fn publish(&self) -> Ordering {
+            if self.relaxed { Ordering::Relaxed } else { Ordering::Release }
+        }
+
+        /// For the load that takes a slot over from the other side.
+        fn observe(&self) -> Ordering {
+            if self.relaxed { Ordering::Relaxed } else { Ordering::Acquire }
+        }
+    }
+
+    impl<T> Drop for Ring<T> {
+        fn drop(&mut self) {
+            // `&mut self`: both sides are gone, so these loads race with nothing
+            let (head, tail) = (self.head.load(Ordering::Relaxed), self.tail.load(Ordering::Relaxed));
+            for index in head..tail {
+                // SAFETY: slots between head and tail hold pushed, unpopped items
+                self.slots[index % self.slots.len()].with_mut(|slot| unsafe { (*slot).assume_init_drop() });
+            }
+        }
+    }
+
+    /// The sending half. Not Clone: there is only ever one producer.
+    pub struct Producer<T> {
+        ring: Arc<Ring<T>>,
+    }
+
+    /// The receiving half. Not Clone: there is only ever one consumer.
+    pub struct Consumer<T> {
+        ring: Arc<Ring<T>>,
+    }
+
+    /// A ring buffer with room for `capacity` items. Panics if it is zero.
+    pub fn ring_buffer<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+        new_ring(capacity, false)
+    }
+
+    /// The same ring with every ordering Relaxed. Broken on purpose, see the tests.
+    pub fn relaxed_ring_buffer<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+        new_ring(capacity, true)
+    }
+
+    fn new_ring<T>(capacity: usize, relaxed: bool) -> (Producer<T>, Consumer<T>) {
+        assert!(capacity > 0, "a ring buffer needs at least one slot");
+        let ring = Arc::new(Ring {
+            slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            relaxed,
+        });
+        (Producer { ring: Arc::clone(&ring) }, Consumer { ring })
+    }
+
+    impl<T> Producer<T> {
+        /// Adds `item`, or hands it back if the ring is full.
+        pub fn push(&mut self, item: T) -> Result<(), T> {
+            let ring = &*self.ring;
+            // Nobody else stores `tail`, so we know its value already
+            let tail = ring.tail.load(Ordering::Relaxed);
+            // Acquire: if the consumer has moved past a slot, it has finished reading it
+            let head = ring.head.load(ring.observe());
+            if tail.wrapping_sub(head) == ring.slots.len() {
+                return Err(item);
+            }
+            // SAFETY: the slot is free, and the consumer will not look at it before `tail` moves on
+            ring.slots[tail % ring.slots.len()].with_mut(|slot| unsafe { (*slot).write(item) });
+            // Release: whoever sees the new tail also sees the item written above
+            ring.tail.store(tail.wrapping_add(1), ring.publish());
+            Ok(())
+        }
+    }
+
+    impl<T> Consumer<T> {
+        /// The oldest item, or `None` if the ring is empty.
+        pub fn pop(&mut self) -> Option<T> {
+            let ring = &*self.ring;
+            let head = ring.head.load(Ordering::Relaxed);
+            // Acquire: pairs with the producer's Release store of `tail`
+            let tail = ring.tail.load(ring.observe());
+            if head == tail {
+                return None;
+            }
+            // SAFETY: the slot holds an item, and the producer will not reuse it before `head` moves on
+            let item = ring.slots[head % ring.slots.len()].with(|slot| unsafe { (*slot).assume_init_read() });
+            // Release: the producer may only overwrite the slot after our read
+            ring.head.store(head.wrapping_add(1), ring.publish());
+            Some(item)
+        }
+    }
+
+    /// Statistics any number of threads can add to without a lock. Every
+    /// field is right on its own, so Relaxed is enough; but a snapshot read
+    /// while threads are recording can mix old and new fields.
+    pub struct Stats {
+        count: AtomicUsize,
+        total: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    impl Stats {
+        pub fn new() -> Stats {
+            Stats { count: AtomicUsize::new(0), total: AtomicUsize::new(0), max: AtomicUsize::new(0) }
+        }
+
+        pub fn record(&self, value: usize) {
+            // One indivisible read-modify-write each: no update can get lost
+            self.count.fetch_add(1, Ordering::Relaxed);
+            self.total.fetch_add(value, Ordering::Relaxed);
+            self.max.fetch_max(value, Ordering::Relaxed);
+        }
+
+        /// What `record` must not do: load, add, store. Another thread can
+        /// store in between, and its update is overwritten. For the tests.
+        pub fn record_racy(&self, value: usize) {
+            let count = self.count.load(Ordering::Relaxed);
+            self.count.store(count + 1, Ordering::Relaxed);
+            let total = self.total.load(Ordering::Relaxed);
+            self.total.store(total + value, Ordering::Relaxed);
+        }
+
+        /// Count, total and largest value recorded.
+        pub fn snapshot(&self) -> (usize, usize, usize) {
+            (self.count.load(Ordering::Relaxed), self.total.load(Ordering::Relaxed), self.max.load(Ordering::Relaxed))
+        }
+    }
+
+    /// The simplest lock there is: one flag, and threads that want it spin
+    /// until they swap it from false to true. Fine for very short critical
+    /// sections; a Mutex puts waiting threads to sleep instead.
+    pub struct SpinLock<T> {
+        locked: AtomicBool,
+        value: UnsafeCell<T>,
+    }
+
+    // SAFETY: the flag lets one thread at a time at `value`
+    unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+    /// Unlocks on drop, so a panicking closure does not leave the lock taken.
+    struct Unlock<'a> {
+        locked: &'a AtomicBool,
+    }
+
+    impl Drop for Unlock<'_> {
+        fn drop(&mut self) {
+            // Release: the next thread to take the lock sees our changes
+            self.locked.store(false, Ordering::Release);
+        }
+    }
+
+    impl<T> SpinLock<T> {
+        pub fn new(value: T) -> SpinLock<T> {
+            SpinLock { locked: AtomicBool::new(false), value: UnsafeCell::new(value) }
+        }
+
+        /// Runs `f` with the lock held.
+        pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+            // Acquire: we see what the previous holder did before unlocking
+            while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+                // Wait with plain loads until it looks free; trying the swap over and over would keep stealing the cache line
+                while self.locked.load(Ordering::Relaxed) {
+                    spin();
+                }
+            }
+            let _unlock = Unlock { locked: &self.locked };
+            // SAFETY: we hold the lock, so this is the only reference
+            self.value.with_mut(|value| f(unsafe { &mut *value }))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use loom::thread;
+
+        /// Sends 1, 2 through a ring of one slot and checks they arrive in order.
+        fn send_two(producer: Producer<usize>, mut consumer: Consumer<usize>) {
+            let sender = thread::spawn(move || {
+                let mut producer = producer;
+                for item in 1..=2 {
+                    while producer.push(item).is_err() {
+                        thread::yield_now();
+                    }
+                }
+            });
+            let mut received = Vec::new();
+            while received.len() < 2 {
+                match consumer.pop() {
+                    Some(item) => received.push(item),
+                    None => thread::yield_now(),
+                }
+            }
+            sender.join().unwrap();
+            assert_eq!(received, [1, 2]);
+        }
+
+        #[test]
+        fn the_ring_buffer_is_right_in_every_interleaving() {
+            // One slot, so the producer also has to wait for the consumer
+            loom::model(|| {
+                let (producer, consumer) = ring_buffer(1);
+                send_two(producer, consumer);
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Causality violation")]
+        fn relaxed_lets_the_consumer_read_a_slot_before_the_item_is_in_it() {
+            // With Relaxed, seeing the new `tail` says nothing about the slot:
+            // loom finds an execution where the read is not ordered after the write
+            loom::model(|| {
+                let (producer, consumer) = relaxed_ring_buffer(1);
+                send_two(producer, consumer);
+            });
+        }
+
+        #[test]
+        fn leftover_items_are_dropped_with_the_ring() {
+            loom::model(|| {
+                let (mut producer, mut consumer) = ring_buffer(3);
+                let item = Arc::new(());
+                for _ in 0..3 {
+                    producer.push(Arc::clone(&item)).unwrap();
+                }
+                assert!(producer.push(Arc::clone(&item)).is_err());
+                drop(consumer.pop());
+                drop((producer, consumer));
+                assert_eq!(Arc::strong_count(&item), 1);
+            });
+        }
+
+        #[test]
+        fn stats_never_lose_an_update() {
+            loom::model(|| {
+                let stats = Arc::new(Stats::new());
+                let other = Arc::clone(&stats);
+                let handle = thread::spawn(move || other.record(5));
+                stats.record(7);
+                handle.join().unwrap();
+                assert_eq!(stats.snapshot(), (2, 12, 7));
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "an update was lost")]
+        fn load_then_store_loses_updates() {
+            loom::model(|| {
+                let stats = Arc::new(Stats::new());
+                let other = Arc::clone(&stats);
+                let handle = thread::spawn(move || other.record_racy(5));
+                stats.record_racy(7);
+                handle.join().unwrap();
+                assert_eq!(stats.snapshot().0, 2, "an update was lost");
+            });
+        }
+
+        #[test]
+        fn the_spinlock_lets_one_thread_in_at_a_time() {
+            loom::model(|| {
+                let lock = Arc::new(SpinLock::new(0));
+                let handles: Vec<_> = (0..2)
+                    .map(|_| {
+                        let lock = Arc::clone(&lock);
+                        thread::spawn(move || lock.with(|n| *n += 1))
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+                assert_eq!(lock.with(|n| *n), 2);
+            });
+        }
+    }
 }
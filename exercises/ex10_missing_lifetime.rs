@@ -0,0 +1,43 @@
+// Exercise 10: Missing Lifetime (error E0106)
+// This exercise comes in two stages, both in this file.
+//
+// Stage 1 (`rusty run ex10_missing_lifetime`): `longer` copies the winner
+// into a new String. There is no need to copy: the caller already has both
+// strings. Change the return type to `&str` and drop the `.to_string()`s.
+// rustc refuses with error[E0106] "missing lifetime specifier": the result
+// borrows from `a` or `b`, and the signature does not say which, so callers
+// cannot know how long it stays valid. The stage passes when that is the one
+// and only error. Then delete the NOT DONE line below.
+//
+// Stage 2 (`rusty run ex10_missing_lifetime_fix`): add a lifetime parameter
+// that ties the result to both arguments, as in 010-lifetimes.rs.
+// I AM NOT DONE
+
+/// Whichever of `a` and `b` has more characters; `a` if they tie.
+pub fn longer(a: &str, b: &str) -> String {
+    // TODO (stage 1): return a `&str` instead.
+    if b.chars().count() > a.chars().count() {
+        b.to_string()
+    } else {
+        a.to_string()
+    }
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_longer_string() {
+        assert_eq!(longer("apple", "fig"), "apple");
+        assert_eq!(longer("fig", "banana"), "banana");
+    }
+
+    #[test]
+    fn ties_go_to_the_first() {
+        assert_eq!(longer("one", "two"), "one");
+        assert_eq!(longer("", ""), "");
+    }
+
+}
@@ -1,5 +1,6 @@
 // Exercise 1: Variables and Data Types
-// Complete the TODOs below
+// Complete the TODOs below, then delete the next line and run `rusty verify`.
+// I AM NOT DONE
 
 fn main() {
     // TODO: Declare an immutable variable 'name' with your name
@@ -0,0 +1,80 @@
+// Exercise 2: Functions
+// Fill in the three functions below, then delete the next line and run
+// `rusty run ex2_functions`. The tests at the bottom say what each one must do.
+// I AM NOT DONE
+
+/// The area of a `width` by `height` rectangle.
+pub fn rectangle_area(width: f64, height: f64) -> f64 {
+    // TODO: multiply the sides
+    todo!()
+}
+
+/// Converts a temperature from Celsius to Fahrenheit: F = C × 9/5 + 32.
+pub fn celsius_to_fahrenheit(celsius: f64) -> f64 {
+    // TODO
+    todo!()
+}
+
+/// `true` for even numbers, including zero and negative ones.
+pub fn is_even(n: i64) -> bool {
+    // TODO: the remainder operator `%` helps here
+    todo!()
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn area_of_rectangles() {
+        assert!(close(rectangle_area(3.0, 4.0), 12.0));
+        assert!(close(rectangle_area(2.5, 4.0), 10.0));
+        assert!(close(rectangle_area(7.0, 0.0), 0.0));
+    }
+
+    #[test]
+    fn area_does_not_depend_on_orientation() {
+        assert!(close(rectangle_area(1.5, 8.0), rectangle_area(8.0, 1.5)));
+    }
+
+    #[test]
+    fn freezing_and_boiling_points() {
+        assert!(close(celsius_to_fahrenheit(0.0), 32.0));
+        assert!(close(celsius_to_fahrenheit(100.0), 212.0));
+    }
+
+    #[test]
+    fn minus_forty_is_the_same_on_both_scales() {
+        assert!(close(celsius_to_fahrenheit(-40.0), -40.0));
+    }
+
+    #[test]
+    fn body_temperature_keeps_its_fraction() {
+        assert!(close(celsius_to_fahrenheit(37.0), 98.6));
+        assert!(close(celsius_to_fahrenheit(36.6), 97.88));
+    }
+
+    #[test]
+    fn even_and_odd() {
+        assert!(is_even(4));
+        assert!(!is_even(7));
+        assert!(is_even(0));
+    }
+
+    #[test]
+    fn negative_numbers_have_parity_too() {
+        assert!(is_even(-2));
+        assert!(!is_even(-3));
+    }
+
+    #[test]
+    fn extreme_values() {
+        assert!(!is_even(i64::MAX));
+        assert!(is_even(i64::MIN));
+    }
+}
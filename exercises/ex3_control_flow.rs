@@ -0,0 +1,88 @@
+// Exercise 3: Control Flow
+// Each function practises one kind of control flow. Fill them in, then delete
+// the next line and run `rusty run ex3_control_flow`.
+// I AM NOT DONE
+
+/// "negative", "zero" or "positive". Use if / else if / else.
+pub fn classify(n: i32) -> &'static str {
+    // TODO
+    todo!()
+}
+
+/// The numbers 1 to 10, in order. Use a `for` loop over a range and push
+/// each number onto a Vec.
+pub fn one_to_ten() -> Vec<u32> {
+    let mut numbers = Vec::new();
+    // TODO
+    numbers
+}
+
+/// Counts down from `from` to 1, e.g. `countdown(5)` is [5, 4, 3, 2, 1].
+/// Use a `while` loop. Counting down from 0 gives an empty Vec.
+pub fn countdown(from: u32) -> Vec<u32> {
+    let mut numbers = Vec::new();
+    // TODO
+    numbers
+}
+
+/// "Fizz" for multiples of 3, "Buzz" for multiples of 5, "FizzBuzz" for
+/// multiples of both, and the number itself otherwise. Try a `match` on
+/// `(n % 3, n % 5)`.
+pub fn fizzbuzz(n: u32) -> String {
+    // TODO
+    todo!()
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_signs() {
+        assert_eq!(classify(-7), "negative");
+        assert_eq!(classify(0), "zero");
+        assert_eq!(classify(42), "positive");
+    }
+
+    #[test]
+    fn classify_extremes() {
+        assert_eq!(classify(i32::MIN), "negative");
+        assert_eq!(classify(i32::MAX), "positive");
+        assert_eq!(classify(-1), "negative");
+        assert_eq!(classify(1), "positive");
+    }
+
+    #[test]
+    fn one_to_ten_in_order() {
+        assert_eq!(one_to_ten(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn countdown_from_five() {
+        assert_eq!(countdown(5), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn countdown_edges() {
+        assert_eq!(countdown(1), vec![1]);
+        assert_eq!(countdown(0), Vec::<u32>::new());
+        assert_eq!(countdown(100).len(), 100);
+    }
+
+    #[test]
+    fn fizzbuzz_words() {
+        assert_eq!(fizzbuzz(3), "Fizz");
+        assert_eq!(fizzbuzz(10), "Buzz");
+        assert_eq!(fizzbuzz(15), "FizzBuzz");
+        assert_eq!(fizzbuzz(7), "7");
+    }
+
+    #[test]
+    fn fizzbuzz_edges() {
+        assert_eq!(fizzbuzz(0), "FizzBuzz");
+        assert_eq!(fizzbuzz(1), "1");
+        assert_eq!(fizzbuzz(90), "FizzBuzz");
+        assert_eq!(fizzbuzz(99), "Fizz");
+    }
+}
@@ -0,0 +1,100 @@
+// Exercise 4: Ownership (Most Important!)
+// These functions take their arguments in three different ways: by value
+// (the function owns it), by shared reference (&) and by mutable reference
+// (&mut). The signatures are fixed; only fill in the bodies. Then delete the
+// next line and run `rusty run ex4_ownership`.
+// I AM NOT DONE
+
+/// Takes ownership of `s` and gives back a new String: `s` in upper case
+/// with "!" on the end. "hello" becomes "HELLO!".
+pub fn shout(s: String) -> String {
+    // TODO
+    todo!()
+}
+
+/// Borrows `s` and counts its characters (not bytes: "héllo" has 5).
+pub fn char_count(s: &String) -> usize {
+    // TODO
+    todo!()
+}
+
+/// Changes the caller's String in place by adding `suffix` to the end.
+pub fn append(s: &mut String, suffix: &str) {
+    // TODO
+}
+
+/// The first word of `s`, borrowed from it: everything before the first
+/// space. A string without spaces is one word.
+pub fn first_word(s: &str) -> &str {
+    // TODO
+    todo!()
+}
+
+/// Moves every String out of `from` and onto the end of `to`, leaving `from`
+/// empty. No cloning!
+pub fn move_all(from: &mut Vec<String>, to: &mut Vec<String>) {
+    // TODO
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shout_takes_ownership() {
+        let greeting = String::from("hello");
+        let loud = shout(greeting);
+        // `greeting` has moved into shout(); only `loud` is left.
+        assert_eq!(loud, "HELLO!");
+    }
+
+    #[test]
+    fn shout_edge_cases() {
+        assert_eq!(shout(String::new()), "!");
+        assert_eq!(shout(String::from("ok, Go")), "OK, GO!");
+    }
+
+    #[test]
+    fn char_count_only_borrows() {
+        let word = String::from("héllo");
+        assert_eq!(char_count(&word), 5);
+        // Still ours: char_count only borrowed it.
+        assert_eq!(word, "héllo");
+        assert_eq!(char_count(&String::new()), 0);
+    }
+
+    #[test]
+    fn append_changes_the_callers_string() {
+        let mut s = String::from("Rust");
+        append(&mut s, "acean");
+        append(&mut s, "");
+        assert_eq!(s, "Rustacean");
+    }
+
+    #[test]
+    fn first_word_of_a_sentence() {
+        let sentence = String::from("borrow checker rules");
+        assert_eq!(first_word(&sentence), "borrow");
+        assert_eq!(first_word("single"), "single");
+    }
+
+    #[test]
+    fn first_word_edge_cases() {
+        assert_eq!(first_word(""), "");
+        assert_eq!(first_word(" leading space"), "");
+        assert_eq!(first_word("día uno"), "día");
+    }
+
+    #[test]
+    fn move_all_empties_the_source() {
+        let mut from = vec![String::from("a"), String::from("b")];
+        let mut to = vec![String::from("z")];
+        move_all(&mut from, &mut to);
+        assert!(from.is_empty());
+        assert_eq!(to, ["z", "a", "b"]);
+
+        move_all(&mut from, &mut to);
+        assert_eq!(to.len(), 3);
+    }
+}
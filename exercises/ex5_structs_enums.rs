@@ -0,0 +1,138 @@
+// Exercise 5: Structs and Enums
+// The types are defined for you; fill in the methods. Then delete the next
+// line and run `rusty run ex5_structs_enums`.
+// I AM NOT DONE
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Person {
+    pub name: String,
+    pub age: u32,
+    pub email: String,
+}
+
+impl Person {
+    pub fn new(name: &str, age: u32, email: &str) -> Person {
+        // TODO
+        todo!()
+    }
+
+    /// 18 or older.
+    pub fn is_adult(&self) -> bool {
+        // TODO
+        todo!()
+    }
+
+    /// "Hi, I'm Ann and I'm 30 years old." (or "1 year old" for a baby).
+    pub fn greeting(&self) -> String {
+        // TODO
+        todo!()
+    }
+
+    /// One year older.
+    pub fn have_birthday(&mut self) {
+        // TODO
+    }
+
+    /// A rough check: exactly one '@', something before it, and a domain
+    /// after it that contains a '.' that is neither its first nor last character.
+    pub fn has_valid_email(&self) -> bool {
+        // TODO
+        todo!()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pet {
+    Dog { name: String },
+    Cat { name: String, indoor: bool },
+    Fish,
+}
+
+impl Pet {
+    /// "Woof!" for dogs, "Meow!" for cats and "..." for fish.
+    pub fn sound(&self) -> &'static str {
+        // TODO: match on self
+        todo!()
+    }
+
+    /// Dogs and cats have names; fish don't.
+    pub fn name(&self) -> Option<&str> {
+        // TODO
+        todo!()
+    }
+
+    /// Dogs always need walks; cats only if they are not indoor cats.
+    pub fn needs_walks(&self) -> bool {
+        // TODO
+        todo!()
+    }
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_fills_every_field() {
+        let ann = Person::new("Ann", 30, "ann@example.com");
+        assert_eq!(ann, Person { name: String::from("Ann"), age: 30, email: String::from("ann@example.com") });
+    }
+
+    #[test]
+    fn adulthood_starts_at_eighteen() {
+        assert!(!Person::new("Kid", 17, "k@example.com").is_adult());
+        assert!(Person::new("Teen", 18, "t@example.com").is_adult());
+        assert!(!Person::new("Baby", 0, "b@example.com").is_adult());
+    }
+
+    #[test]
+    fn greetings() {
+        assert_eq!(Person::new("Ann", 30, "").greeting(), "Hi, I'm Ann and I'm 30 years old.");
+        assert_eq!(Person::new("Bo", 1, "").greeting(), "Hi, I'm Bo and I'm 1 year old.");
+        assert_eq!(Person::new("Cy", 0, "").greeting(), "Hi, I'm Cy and I'm 0 years old.");
+    }
+
+    #[test]
+    fn birthdays_change_the_person() {
+        let mut kid = Person::new("Kid", 17, "k@example.com");
+        kid.have_birthday();
+        assert_eq!(kid.age, 18);
+        assert!(kid.is_adult());
+    }
+
+    #[test]
+    fn valid_emails() {
+        assert!(Person::new("A", 1, "ann@example.com").has_valid_email());
+        assert!(Person::new("A", 1, "a.b@mail.example.org").has_valid_email());
+    }
+
+    #[test]
+    fn invalid_emails() {
+        for email in ["", "ann", "@example.com", "ann@", "ann@example", "ann@@example.com", "a@b@c.com", "ann@.com", "ann@example."] {
+            assert!(!Person::new("A", 1, email).has_valid_email(), "{:?} should be invalid", email);
+        }
+    }
+
+    #[test]
+    fn pet_sounds() {
+        assert_eq!(Pet::Dog { name: String::from("Rex") }.sound(), "Woof!");
+        assert_eq!(Pet::Cat { name: String::from("Tom"), indoor: true }.sound(), "Meow!");
+        assert_eq!(Pet::Fish.sound(), "...");
+    }
+
+    #[test]
+    fn pet_names() {
+        assert_eq!(Pet::Dog { name: String::from("Rex") }.name(), Some("Rex"));
+        assert_eq!(Pet::Cat { name: String::from("Tom"), indoor: false }.name(), Some("Tom"));
+        assert_eq!(Pet::Fish.name(), None);
+    }
+
+    #[test]
+    fn walks() {
+        assert!(Pet::Dog { name: String::from("Rex") }.needs_walks());
+        assert!(Pet::Cat { name: String::from("Tom"), indoor: false }.needs_walks());
+        assert!(!Pet::Cat { name: String::from("Kit"), indoor: true }.needs_walks());
+        assert!(!Pet::Fish.needs_walks());
+    }
+}
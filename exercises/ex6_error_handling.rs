@@ -0,0 +1,115 @@
+// Exercise 6: Error Handling
+// No unwrap() or expect() allowed in your solutions: return a Result or an
+// Option instead and let the caller decide. Then delete the next line and
+// run `rusty run ex6_error_handling`.
+// I AM NOT DONE
+
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum AgeError {
+    /// Nothing but whitespace was entered.
+    Empty,
+    /// The input is not a whole number.
+    NotANumber(String),
+    /// A number, but not a plausible age (0 to 150).
+    OutOfRange(i64),
+}
+
+/// Parses an age typed by a user. Surrounding whitespace is fine.
+pub fn parse_age(input: &str) -> Result<u8, AgeError> {
+    // TODO: trim, check for empty, parse as i64, check the range
+    todo!()
+}
+
+/// Divides `a` by `b`, or `None` if that is impossible: dividing by zero, or
+/// i32::MIN / -1, which does not fit in an i32. (`checked_div` exists, but
+/// try writing the checks yourself.)
+pub fn safe_divide(a: i32, b: i32) -> Option<i32> {
+    // TODO
+    todo!()
+}
+
+/// The first even number in the slice, if there is one.
+pub fn first_even(numbers: &[i32]) -> Option<i32> {
+    // TODO
+    todo!()
+}
+
+/// Adds up numbers given as text. The first one that does not parse is the
+/// error. Use the `?` operator.
+pub fn sum_all(inputs: &[&str]) -> Result<i32, ParseIntError> {
+    // TODO
+    todo!()
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plausible_ages() {
+        assert_eq!(parse_age("42"), Ok(42));
+        assert_eq!(parse_age("  7\n"), Ok(7));
+        assert_eq!(parse_age("0"), Ok(0));
+        assert_eq!(parse_age("150"), Ok(150));
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(parse_age(""), Err(AgeError::Empty));
+        assert_eq!(parse_age("   "), Err(AgeError::Empty));
+    }
+
+    #[test]
+    fn not_numbers() {
+        assert_eq!(parse_age("forty"), Err(AgeError::NotANumber(String::from("forty"))));
+        assert_eq!(parse_age(" 4.5 "), Err(AgeError::NotANumber(String::from("4.5"))));
+        assert_eq!(parse_age("99999999999999999999"), Err(AgeError::NotANumber(String::from("99999999999999999999"))));
+    }
+
+    #[test]
+    fn implausible_ages() {
+        assert_eq!(parse_age("151"), Err(AgeError::OutOfRange(151)));
+        assert_eq!(parse_age("-1"), Err(AgeError::OutOfRange(-1)));
+        assert_eq!(parse_age("256"), Err(AgeError::OutOfRange(256)));
+    }
+
+    #[test]
+    fn division() {
+        assert_eq!(safe_divide(10, 3), Some(3));
+        assert_eq!(safe_divide(-9, 3), Some(-3));
+        assert_eq!(safe_divide(0, 5), Some(0));
+    }
+
+    #[test]
+    fn impossible_division() {
+        assert_eq!(safe_divide(1, 0), None);
+        assert_eq!(safe_divide(0, 0), None);
+        assert_eq!(safe_divide(i32::MIN, -1), None);
+        assert_eq!(safe_divide(i32::MIN, 1), Some(i32::MIN));
+    }
+
+    #[test]
+    fn finding_even_numbers() {
+        assert_eq!(first_even(&[1, 3, 4, 6]), Some(4));
+        assert_eq!(first_even(&[-3, -2]), Some(-2));
+        assert_eq!(first_even(&[1, 3, 5]), None);
+        assert_eq!(first_even(&[]), None);
+    }
+
+    #[test]
+    fn summing_text() {
+        assert_eq!(sum_all(&["1", "2", "39"]), Ok(42));
+        assert_eq!(sum_all(&[]), Ok(0));
+        assert_eq!(sum_all(&["-5", "5"]), Ok(0));
+    }
+
+    #[test]
+    fn the_first_bad_number_is_the_error() {
+        let error = sum_all(&["1", "two", "x"]).unwrap_err();
+        assert_eq!(error, "two".parse::<i32>().unwrap_err());
+        assert!(sum_all(&["1", ""]).is_err());
+    }
+}
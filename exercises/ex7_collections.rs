@@ -0,0 +1,110 @@
+// Exercise 7: Collections
+// Practise Vec, HashMap and iterating over both. Fill in the functions, then
+// delete the next line and run `rusty run ex7_collections`.
+// I AM NOT DONE
+
+use std::collections::HashMap;
+
+/// The mean of the numbers, or `None` for an empty slice.
+pub fn average(numbers: &[f64]) -> Option<f64> {
+    // TODO
+    todo!()
+}
+
+/// A new Vec with only the even numbers, in their original order.
+pub fn evens(numbers: &[i32]) -> Vec<i32> {
+    // TODO
+    todo!()
+}
+
+/// How often each word appears. Words are separated by whitespace, counted
+/// case-insensitively (stored in lower case), and any punctuation at the
+/// start or end of a word is ignored: "Hello, hello!" counts "hello" twice.
+pub fn word_counts(text: &str) -> HashMap<String, usize> {
+    // TODO: the entry API (`.entry(word).or_insert(0)`) is handy here
+    todo!()
+}
+
+/// The most frequent word and its count. Ties go to the word that comes first
+/// alphabetically. `None` if there are no words at all.
+pub fn most_common(text: &str) -> Option<(String, usize)> {
+    // TODO: build on word_counts()
+    todo!()
+}
+
+/// Groups words by their first letter (lower case), keeping each group in the
+/// order the words appeared.
+pub fn group_by_initial(words: &[&str]) -> HashMap<char, Vec<String>> {
+    // TODO
+    todo!()
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages() {
+        assert_eq!(average(&[1.0, 2.0, 3.0, 4.0]), Some(2.5));
+        assert_eq!(average(&[-3.0]), Some(-3.0));
+        assert_eq!(average(&[]), None);
+    }
+
+    #[test]
+    fn keeping_evens() {
+        assert_eq!(evens(&[5, 2, 8, 3, -4, 0]), vec![2, 8, -4, 0]);
+        assert_eq!(evens(&[1, 3]), Vec::<i32>::new());
+        assert_eq!(evens(&[]), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn counting_words() {
+        let counts = word_counts("the cat and the hat");
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts["the"], 2);
+        assert_eq!(counts["cat"], 1);
+    }
+
+    #[test]
+    fn counting_ignores_case_and_punctuation() {
+        let counts = word_counts("Hello, hello! HELLO... (hello)");
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts["hello"], 4);
+        assert_eq!(word_counts("don't stop")["don't"], 1);
+    }
+
+    #[test]
+    fn counting_nothing() {
+        assert!(word_counts("").is_empty());
+        assert!(word_counts("  \n\t ").is_empty());
+        assert!(word_counts("... !!").is_empty());
+    }
+
+    #[test]
+    fn most_common_word() {
+        assert_eq!(most_common("a b a c a b"), Some((String::from("a"), 3)));
+        assert_eq!(most_common(""), None);
+    }
+
+    #[test]
+    fn ties_go_to_the_alphabetically_first_word() {
+        assert_eq!(most_common("pear apple pear apple fig"), Some((String::from("apple"), 2)));
+        assert_eq!(most_common("zebra yak"), Some((String::from("yak"), 1)));
+    }
+
+    #[test]
+    fn grouping_words() {
+        let groups = group_by_initial(&["apple", "Banana", "avocado", "blueberry", "cherry"]);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&'a'], ["apple", "avocado"]);
+        assert_eq!(groups[&'b'], ["Banana", "blueberry"]);
+        assert_eq!(groups[&'c'], ["cherry"]);
+    }
+
+    #[test]
+    fn grouping_skips_empty_words() {
+        assert!(group_by_initial(&[]).is_empty());
+        assert!(group_by_initial(&[""]).is_empty());
+    }
+}
@@ -0,0 +1,42 @@
+// Exercise 8: Use After Move (error E0382)
+// This exercise comes in two stages, both in this file.
+//
+// Stage 1 (`rusty run ex8_use_after_move`): break it on purpose. The
+// `.clone()` in `headline` is only there to keep the compiler quiet. Delete
+// it, and rustc refuses with error[E0382] "borrow of moved value". The stage
+// passes when that is the one and only error. Read the message: it points at
+// the move and at the later use. Then delete the NOT DONE line below.
+//
+// Stage 2 (`rusty run ex8_use_after_move_fix`): make it compile again
+// *without* putting the clone back. `shout` only reads its argument, so it
+// has no business owning it.
+// I AM NOT DONE
+
+/// The text in capitals with "!" on the end: "hello" becomes "HELLO!".
+pub fn shout(text: String) -> String {
+    text.to_uppercase() + "!"
+}
+
+/// The shouted title followed by its length: "HELLO! (5 letters)".
+pub fn headline(title: String) -> String {
+    // TODO (stage 1): remove `.clone()`.
+    let loud = shout(title.clone());
+    format!("{} ({} letters)", loud, title.chars().count())
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headline_shouts_and_counts() {
+        assert_eq!(headline(String::from("hello")), "HELLO! (5 letters)");
+        assert_eq!(headline(String::from("ferris")), "FERRIS! (6 letters)");
+    }
+
+    #[test]
+    fn letters_are_counted_not_bytes() {
+        assert_eq!(headline(String::from("çay")), "ÇAY! (3 letters)");
+    }
+}
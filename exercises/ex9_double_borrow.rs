@@ -0,0 +1,60 @@
+// Exercise 9: Two Mutable Borrows (error E0499)
+// This exercise comes in two stages, both in this file.
+//
+// Stage 1 (`rusty run ex9_double_borrow`): `swap_ends` works by copying
+// values around. Rewrite its body to take two mutable references instead,
+// one to the first item and one to the last, and hand them to
+// `std::mem::swap`:
+//
+//     let first = &mut items[0];
+//     let end = &mut items[last];
+//     std::mem::swap(first, end);
+//
+// rustc refuses with error[E0499] "cannot borrow ... as mutable more than
+// once at a time". It cannot tell that the two indexes are different, and
+// two `&mut` to the same data could trample each other. The stage passes
+// when that is the one and only error. Then delete the NOT DONE line below.
+//
+// Stage 2 (`rusty run ex9_double_borrow_fix`): keep `std::mem::swap`, but get
+// the two references in a way the compiler can check. `split_at_mut` splits
+// one `&mut [T]` into two that cannot overlap.
+// I AM NOT DONE
+
+/// Swaps the first and last items. Slices shorter than two are left alone.
+pub fn swap_ends(items: &mut [i32]) {
+    if items.len() < 2 {
+        return;
+    }
+    let last = items.len() - 1;
+    // TODO (stage 1): replace these three lines.
+    let first = items[0];
+    items[0] = items[last];
+    items[last] = first;
+}
+
+// Tests: you don't need to change anything below this line.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ends_trade_places() {
+        let mut numbers = [1, 2, 3, 4];
+        swap_ends(&mut numbers);
+        assert_eq!(numbers, [4, 2, 3, 1]);
+
+        let mut pair = vec![7, 9];
+        swap_ends(&mut pair);
+        assert_eq!(pair, [9, 7]);
+    }
+
+    #[test]
+    fn short_slices_are_left_alone() {
+        let mut one = [5];
+        swap_ends(&mut one);
+        assert_eq!(one, [5]);
+
+        let mut none: [i32; 0] = [];
+        swap_ends(&mut none);
+    }
+}
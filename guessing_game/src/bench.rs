@@ -0,0 +1,133 @@
+// bench.rs
+// Plays many simulated games per strategy and summarizes how many guesses they took.
+
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::game::{Feedback, Fixed, Game, Rng};
+use crate::strategy::Strategy;
+
+/// Plays one game against `secret` and returns the number of guesses used.
+pub fn play_one(strategy: &mut dyn Strategy, range: RangeInclusive<u32>, secret: u32) -> u32 {
+    let mut game = Game::new(range.clone(), None, &mut Fixed(secret));
+    strategy.reset(range);
+
+    loop {
+        let guess = strategy.next_guess();
+        match game.guess(guess) {
+            Feedback::Correct => return game.attempts(),
+            Feedback::OutOfRange | Feedback::GameOver => {
+                panic!("strategy {} guessed {} outside the game", strategy.name(), guess)
+            }
+            feedback => strategy.observe(guess, feedback),
+        }
+    }
+}
+
+/// Guess counts for every simulated game of one strategy.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub strategy: &'static str,
+    /// Sorted ascending.
+    pub guesses: Vec<u32>,
+}
+
+impl BenchResult {
+    pub fn mean(&self) -> f64 {
+        if self.guesses.is_empty() {
+            return 0.0;
+        }
+        self.guesses.iter().map(|&g| g as f64).sum::<f64>() / self.guesses.len() as f64
+    }
+
+    pub fn median(&self) -> f64 {
+        let n = self.guesses.len();
+        match n {
+            0 => 0.0,
+            _ if n % 2 == 1 => self.guesses[n / 2] as f64,
+            _ => (self.guesses[n / 2 - 1] + self.guesses[n / 2]) as f64 / 2.0,
+        }
+    }
+
+    pub fn max(&self) -> u32 {
+        self.guesses.last().copied().unwrap_or(0)
+    }
+
+    /// Counts games per bucket of guess counts, using at most `buckets` equal-width buckets
+    /// starting at 1 guess.
+    pub fn histogram(&self, buckets: usize) -> Vec<(RangeInclusive<u32>, usize)> {
+        let max = self.max();
+        if max == 0 || buckets == 0 {
+            return Vec::new();
+        }
+        let width = max.div_ceil(buckets as u32);
+
+        let mut counts = vec![0; max.div_ceil(width) as usize];
+        for &g in &self.guesses {
+            counts[((g - 1) / width) as usize] += 1;
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let start = i as u32 * width + 1;
+                (start..=start + width - 1, count)
+            })
+            .collect()
+    }
+}
+
+/// Plays `games` games with secrets drawn from `seed`, so runs are reproducible
+/// and every strategy faces the same secrets.
+pub fn run(strategy: &mut dyn Strategy, range: RangeInclusive<u32>, games: usize, seed: u64) -> BenchResult {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut guesses: Vec<u32> = (0..games)
+        .map(|_| {
+            let secret = rng.pick(range.clone());
+            play_one(strategy, range.clone(), secret)
+        })
+        .collect();
+    guesses.sort_unstable();
+
+    BenchResult { strategy: strategy.name(), guesses }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{self, BinarySearch, LinearScan};
+
+    #[test]
+    fn statistics() {
+        let result = BenchResult { strategy: "test", guesses: vec![1, 2, 3, 10] };
+        assert_eq!(result.mean(), 4.0);
+        assert_eq!(result.median(), 2.5);
+        assert_eq!(result.max(), 10);
+
+        let histogram = result.histogram(5);
+        assert_eq!(histogram[0], (1..=2, 2));
+        assert_eq!(histogram[1], (3..=4, 1));
+        assert_eq!(histogram[4], (9..=10, 1));
+        assert_eq!(histogram.iter().map(|(_, c)| c).sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn runs_are_reproducible() {
+        let a = run(&mut strategy::RandomInBounds::new(3), 1..=100, 200, 42);
+        let b = run(&mut strategy::RandomInBounds::new(3), 1..=100, 200, 42);
+        assert_eq!(a.guesses, b.guesses);
+    }
+
+    #[test]
+    fn binary_beats_linear() {
+        let binary = run(&mut BinarySearch::new(), 1..=100, 500, 1);
+        let linear = run(&mut LinearScan::new(), 1..=100, 500, 1);
+
+        assert!(binary.max() <= 7);
+        assert!(binary.mean() < linear.mean());
+        assert_eq!(binary.guesses.len(), 500);
+    }
+}
@@ -0,0 +1,454 @@
+// bulls_cows.rs
+// Bulls and cows (a.k.a. Mastermind): guess a multi-symbol code.
+// A bull is a right symbol in the right place, a cow a right symbol in the wrong place.
+
+use std::fmt;
+
+use crate::game::Rng;
+use crate::t;
+
+/// The largest number of codes the solver is willing to enumerate.
+const MAX_CODES: usize = 100_000;
+
+/// How many scores the solver may compute to pick one guess. Past this it
+/// only tries as many of the remaining candidates as the budget allows.
+const SOLVER_BUDGET: usize = 10_000_000;
+
+/// Keeps symbol counts on the stack while scoring.
+const MAX_SYMBOLS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    length: usize,
+    alphabet: Vec<char>,
+    duplicates: bool,
+    max_attempts: Option<u32>,
+}
+
+impl Rules {
+    pub fn new(length: usize, alphabet: &str, duplicates: bool, max_attempts: Option<u32>) -> Result<Rules, String> {
+        let symbols: Vec<char> = alphabet.chars().collect();
+
+        if length == 0 {
+            return Err(t!("code_length_zero"));
+        }
+        if symbols.len() < 2 || symbols.len() > MAX_SYMBOLS {
+            return Err(t!("alphabet_size", max = MAX_SYMBOLS));
+        }
+        if symbols.iter().any(|c| c.is_whitespace()) {
+            return Err(t!("alphabet_spaces"));
+        }
+        for (i, c) in symbols.iter().enumerate() {
+            if symbols[..i].contains(c) {
+                return Err(t!("alphabet_repeats", symbol = c));
+            }
+        }
+        if !duplicates && length > symbols.len() {
+            return Err(t!("alphabet_too_small", length = length));
+        }
+        if max_attempts == Some(0) {
+            return Err(t!("attempts_zero"));
+        }
+
+        Ok(Rules { length, alphabet: symbols, duplicates, max_attempts })
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn alphabet(&self) -> String {
+        self.alphabet.iter().collect()
+    }
+
+    pub fn duplicates(&self) -> bool {
+        self.duplicates
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Reads a code such as "4271", checking length, symbols and duplicates.
+    pub fn parse(&self, input: &str) -> Result<Code, String> {
+        let input = input.trim();
+        let mut symbols = Vec::with_capacity(self.length);
+        for c in input.chars() {
+            match self.alphabet.iter().position(|&a| a == c) {
+                Some(index) => symbols.push(index as u8),
+                None => return Err(t!("not_a_symbol", symbol = c, alphabet = self.alphabet())),
+            }
+        }
+
+        if symbols.len() != self.length {
+            return Err(t!("code_wrong_length", length = self.length, found = symbols.len()));
+        }
+        if !self.duplicates {
+            for (i, s) in symbols.iter().enumerate() {
+                if symbols[..i].contains(s) {
+                    return Err(t!("symbol_repeats", symbol = self.alphabet[*s as usize]));
+                }
+            }
+        }
+        Ok(Code(symbols))
+    }
+
+    pub fn show(&self, code: &Code) -> String {
+        code.0.iter().map(|&s| self.alphabet[s as usize]).collect()
+    }
+
+    pub fn random_code<R: Rng + ?Sized>(&self, rng: &mut R) -> Code {
+        let mut pool: Vec<u8> = (0..self.alphabet.len() as u8).collect();
+        let mut symbols = Vec::with_capacity(self.length);
+        for _ in 0..self.length {
+            let index = rng.pick(0..=(pool.len() - 1) as u32) as usize;
+            if self.duplicates {
+                symbols.push(pool[index]);
+            } else {
+                symbols.push(pool.swap_remove(index));
+            }
+        }
+        Code(symbols)
+    }
+
+    /// Every code these rules allow, in lexicographic order.
+    pub fn all_codes(&self) -> Result<Vec<Code>, String> {
+        let total = (self.alphabet.len() as u64).checked_pow(self.length as u32);
+        if total.is_none_or(|t| t > MAX_CODES as u64) {
+            return Err(t!("too_many_codes", limit = MAX_CODES));
+        }
+
+        let base = self.alphabet.len() as u8;
+        let mut codes = Vec::new();
+        let mut current = vec![0u8; self.length];
+        loop {
+            if self.duplicates || (0..self.length).all(|i| !current[..i].contains(&current[i])) {
+                codes.push(Code(current.clone()));
+            }
+
+            // Odometer increment, rightmost symbol first.
+            let mut position = self.length;
+            loop {
+                if position == 0 {
+                    return Ok(codes);
+                }
+                position -= 1;
+                current[position] += 1;
+                if current[position] < base {
+                    break;
+                }
+                current[position] = 0;
+            }
+        }
+    }
+
+    pub fn score(&self, secret: &Code, guess: &Code) -> Score {
+        let bulls = secret.0.iter().zip(&guess.0).filter(|(a, b)| a == b).count();
+
+        let mut secret_counts = [0usize; MAX_SYMBOLS];
+        let mut guess_counts = [0usize; MAX_SYMBOLS];
+        for (&s, &g) in secret.0.iter().zip(&guess.0) {
+            secret_counts[s as usize] += 1;
+            guess_counts[g as usize] += 1;
+        }
+        let symbols = self.alphabet.len();
+        let common: usize = secret_counts[..symbols]
+            .iter()
+            .zip(&guess_counts[..symbols])
+            .map(|(a, b)| a.min(b))
+            .sum();
+
+        Score { bulls, cows: common - bulls }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::new(4, "0123456789", false, Some(10)).expect("valid default rules")
+    }
+}
+
+/// A code as indices into the alphabet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Code(Vec<u8>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub bulls: usize,
+    pub cows: usize,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", t!("bulls_score", bulls = self.bulls, cows = self.cows))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Playing,
+    Won,
+    OutOfAttempts,
+}
+
+/// One round of bulls and cows against a hidden code.
+#[derive(Debug, Clone)]
+pub struct CodeGame {
+    rules: Rules,
+    secret: Code,
+    attempts: u32,
+    outcome: Outcome,
+}
+
+impl CodeGame {
+    pub fn new<R: Rng + ?Sized>(rules: Rules, rng: &mut R) -> CodeGame {
+        let secret = rules.random_code(rng);
+        CodeGame::with_secret(rules, secret)
+    }
+
+    pub fn with_secret(rules: Rules, secret: Code) -> CodeGame {
+        CodeGame { rules, secret, attempts: 0, outcome: Outcome::Playing }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Scores a guess. Malformed guesses are rejected without costing an attempt.
+    pub fn guess(&mut self, input: &str) -> Result<Score, String> {
+        if self.outcome != Outcome::Playing {
+            return Err(t!("code_game_over"));
+        }
+        let guess = self.rules.parse(input)?;
+
+        self.attempts += 1;
+        let score = self.rules.score(&self.secret, &guess);
+        if score.bulls == self.rules.length {
+            self.outcome = Outcome::Won;
+        } else if self.rules.max_attempts.is_some_and(|max| self.attempts >= max) {
+            self.outcome = Outcome::OutOfAttempts;
+        }
+        Ok(score)
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.rules.max_attempts.map(|max| max.saturating_sub(self.attempts))
+    }
+
+    pub fn secret(&self) -> String {
+        self.rules.show(&self.secret)
+    }
+}
+
+/// Knuth's minimax solver: each guess minimizes the worst-case number of
+/// codes that could remain afterwards.
+pub struct Solver {
+    rules: Rules,
+    all: Vec<Code>,
+    candidates: Vec<Code>,
+}
+
+impl Solver {
+    pub fn new(rules: Rules) -> Result<Solver, String> {
+        let all = rules.all_codes()?;
+        Ok(Solver { candidates: all.clone(), all, rules })
+    }
+
+    /// How many codes are still consistent with every score so far.
+    pub fn remaining(&self) -> usize {
+        self.candidates.len()
+    }
+
+    /// The next guess, or `None` if the scores so far contradict each other.
+    pub fn next_guess(&self) -> Option<Code> {
+        match self.candidates.len() {
+            0 => return None,
+            1 | 2 => return Some(self.candidates[0].clone()),
+            _ => {}
+        }
+        // The opening is fixed, which saves the most expensive minimax pass.
+        // Without duplicates every opening is equivalent by symmetry; with
+        // them, Knuth's 1122 (half one symbol, half another) is the best.
+        if self.candidates.len() == self.all.len() {
+            if !self.rules.duplicates {
+                return Some(self.all[0].clone());
+            }
+            let length = self.rules.length;
+            return Some(Code((0..length).map(|i| (i * 2 / length) as u8).collect()));
+        }
+
+        // Trying every code is too slow for big rule sets; then only try
+        // remaining candidates, which are always worth guessing.
+        let guesses = if self.all.len() * self.candidates.len() <= SOLVER_BUDGET {
+            &self.all[..]
+        } else {
+            let affordable = (SOLVER_BUDGET / self.candidates.len()).max(1);
+            &self.candidates[..affordable.min(self.candidates.len())]
+        };
+
+        let width = self.rules.length + 1;
+        let mut best: Option<(usize, bool, &Code)> = None;
+        let mut partitions = vec![0usize; width * width];
+
+        for guess in guesses {
+            partitions.iter_mut().for_each(|p| *p = 0);
+            for candidate in &self.candidates {
+                let score = self.rules.score(candidate, guess);
+                partitions[score.bulls * width + score.cows] += 1;
+            }
+            let worst = *partitions.iter().max().unwrap_or(&0);
+
+            // Smaller worst case wins; on a tie prefer a guess that could be the answer.
+            let better = match best {
+                None => true,
+                Some((best_worst, _, _)) if worst < best_worst => true,
+                Some((best_worst, best_is_candidate, _)) if worst == best_worst && !best_is_candidate => {
+                    self.candidates.contains(guess)
+                }
+                _ => false,
+            };
+            if better {
+                best = Some((worst, self.candidates.contains(guess), guess));
+            }
+        }
+        best.map(|(_, _, code)| code.clone())
+    }
+
+    /// Keeps only the codes that would have produced `score` for `guess`.
+    pub fn record(&mut self, guess: &Code, score: Score) {
+        let rules = &self.rules;
+        self.candidates.retain(|c| rules.score(c, guess) == score);
+    }
+}
+
+/// Plays `secret` with the solver and returns every guess with its score.
+pub fn solve(rules: &Rules, secret: &Code) -> Result<Vec<(Code, Score)>, String> {
+    let mut solver = Solver::new(rules.clone())?;
+    let mut steps = Vec::new();
+    while let Some(guess) = solver.next_guess() {
+        let score = rules.score(secret, &guess);
+        steps.push((guess.clone(), score));
+        if score.bulls == rules.length {
+            return Ok(steps);
+        }
+        solver.record(&guess, score);
+    }
+    Err(t!("no_consistent_code"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Fixed;
+
+    fn classic() -> Rules {
+        // Knuth's original setting: 4 pegs, 6 colours, repeats allowed.
+        Rules::new(4, "123456", true, None).unwrap()
+    }
+
+    #[test]
+    fn scoring_counts_bulls_and_cows() {
+        let rules = Rules::default();
+        let secret = rules.parse("4271").unwrap();
+
+        assert_eq!(rules.score(&secret, &rules.parse("1234").unwrap()), Score { bulls: 1, cows: 2 });
+        assert_eq!(rules.score(&secret, &rules.parse("4271").unwrap()), Score { bulls: 4, cows: 0 });
+        assert_eq!(rules.score(&secret, &rules.parse("5690").unwrap()), Score { bulls: 0, cows: 0 });
+    }
+
+    #[test]
+    fn scoring_with_duplicates_does_not_double_count() {
+        let rules = classic();
+        let secret = rules.parse("1122").unwrap();
+
+        assert_eq!(rules.score(&secret, &rules.parse("2211").unwrap()), Score { bulls: 0, cows: 4 });
+        assert_eq!(rules.score(&secret, &rules.parse("1111").unwrap()), Score { bulls: 2, cows: 0 });
+        assert_eq!(rules.score(&secret, &rules.parse("3331").unwrap()), Score { bulls: 0, cows: 1 });
+    }
+
+    #[test]
+    fn malformed_guesses_are_rejected() {
+        let rules = Rules::default();
+        assert!(rules.parse("123").is_err());
+        assert!(rules.parse("12a4").is_err());
+        assert!(rules.parse("1224").is_err());
+        assert!(classic().parse("1224").is_ok());
+        assert!(Rules::new(5, "0123", false, None).is_err());
+        assert!(Rules::new(3, "0010", true, None).is_err());
+    }
+
+    #[test]
+    fn game_tracks_attempts_and_outcome() {
+        let rules = Rules::new(3, "abcdef", false, Some(2)).unwrap();
+        let secret = rules.parse("fab").unwrap();
+        let mut game = CodeGame::with_secret(rules, secret);
+
+        assert!(game.guess("aaa").is_err());
+        assert_eq!(game.attempts(), 0);
+        assert_eq!(game.guess("abc"), Ok(Score { bulls: 0, cows: 2 }));
+        assert_eq!(game.guess("fab"), Ok(Score { bulls: 3, cows: 0 }));
+        assert_eq!(game.outcome(), Outcome::Won);
+        assert!(game.guess("fab").is_err());
+    }
+
+    #[test]
+    fn random_codes_follow_the_rules() {
+        let rules = Rules::default();
+        for seed in 0..20 {
+            let code = rules.random_code(&mut Fixed(seed % 7));
+            assert!(rules.parse(&rules.show(&code)).is_ok());
+        }
+    }
+
+    #[test]
+    fn enumeration_counts() {
+        assert_eq!(classic().all_codes().unwrap().len(), 1296);
+        assert_eq!(Rules::default().all_codes().unwrap().len(), 5040);
+        assert!(Rules::new(8, "0123456789", true, None).unwrap().all_codes().is_err());
+    }
+
+    #[test]
+    fn knuth_solves_classic_mastermind_in_five() {
+        let rules = classic();
+        // A spread of secrets; Knuth proved five guesses always suffice.
+        for secret in ["1111", "1234", "2266"] {
+            let secret = rules.parse(secret).unwrap();
+            let steps = solve(&rules, &secret).unwrap();
+            assert!(steps.len() <= 5, "{} took {} guesses", rules.show(&secret), steps.len());
+            assert_eq!(steps.last().unwrap().0, secret);
+        }
+    }
+
+    #[test]
+    fn big_rule_sets_are_solved_in_reasonable_time() {
+        // 100,000 codes: a full minimax pass would score 10^10 pairs
+        let rules = Rules::new(5, "0123456789", true, None).unwrap();
+        let started = std::time::Instant::now();
+        let secret = rules.parse("12345").unwrap();
+        let steps = solve(&rules, &secret).unwrap();
+
+        assert_eq!(steps.last().unwrap().0, secret);
+        assert_eq!(rules.show(&steps[0].0), "00011");
+        assert!(started.elapsed() < std::time::Duration::from_secs(60), "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn inconsistent_scores_leave_no_guess() {
+        let rules = Rules::new(2, "abc", false, None).unwrap();
+        let mut solver = Solver::new(rules.clone()).unwrap();
+        let ab = rules.parse("ab").unwrap();
+        solver.record(&ab, Score { bulls: 2, cows: 0 });
+        solver.record(&rules.parse("ba").unwrap(), Score { bulls: 2, cows: 0 });
+
+        assert_eq!(solver.remaining(), 0);
+        assert_eq!(solver.next_guess(), None);
+    }
+}
@@ -0,0 +1,442 @@
+// cli.rs
+// Turns command-line arguments into the thing the binary should do.
+
+use std::env;
+
+use crate::bulls_cows::Rules;
+use crate::config::{next_value, parse_number, Config};
+use crate::i18n::Locale;
+use crate::multiplayer::Mode;
+use crate::strategy;
+use crate::t;
+
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Commands, each with the catalog key of its description.
+const COMMANDS: [(&str, &str); 8] = [
+    ("guessing_game [options]", "usage_play"),
+    ("guessing_game reverse [options]", "usage_reverse"),
+    ("guessing_game bench [options] [--games <n>] [--seed <n>] [--strategy <name>]", "usage_bench"),
+    ("guessing_game serve [options] [--port <n>] [--mode race|turns] [--host <addr>]", "usage_serve"),
+    ("guessing_game join [--host <addr>] [--port <n>] [--name <player>]", "usage_join"),
+    ("guessing_game bulls [--length <n>] [--alphabet <symbols>] [--duplicates] [--attempts <n>]", "usage_bulls"),
+    ("guessing_game bulls [rules] --solve <code>", "usage_solve"),
+    ("guessing_game replay <log>", "usage_replay"),
+];
+
+/// Options, each with the catalog key of its description.
+const OPTIONS: [(&str, &str); 13] = [
+    ("--easy | --normal | --hard", "option_preset"),
+    ("--difficulty <name>", "option_difficulty"),
+    ("--min <n> --max <n>", "option_range"),
+    ("--attempts <n>", "option_attempts"),
+    ("--no-hints", "option_no_hints"),
+    ("--hint-budget <n>", "option_hint_budget"),
+    ("--name <player>", "option_name"),
+    ("--seed <n>", "option_seed"),
+    ("--tui", "option_tui"),
+    ("--resume", "option_resume"),
+    ("--script <file>", "option_script"),
+    ("--scores", "option_scores"),
+    ("--lang <code>", "option_lang"),
+];
+
+/// Descriptions are wrapped to this many characters.
+const DESCRIPTION_WIDTH: usize = 54;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Play { config: Config, player: String, seed: Option<u64>, script: Option<String>, tui: bool, resume: bool },
+    Scores,
+    Reverse { config: Config },
+    Bench { config: Config, games: usize, seed: u64, strategy: Option<String> },
+    Serve { config: Config, host: String, port: u16, mode: Mode },
+    Join { host: String, port: u16, player: String },
+    Bulls { rules: Rules, solve: Option<String> },
+    Replay { log: String },
+}
+
+/// The usage text in the current language.
+pub fn usage() -> String {
+    let label = t!("usage_label");
+    let indent = " ".repeat(label.chars().count() + 1);
+
+    let mut text = String::new();
+    for (i, (command, key)) in COMMANDS.iter().enumerate() {
+        let lead = if i == 0 { format!("{} ", label) } else { indent.clone() };
+        entry(&mut text, &lead, command, 33, &t!(key));
+    }
+    text.push_str(&format!("\n{}\n", t!("options_label")));
+    for (option, key) in OPTIONS {
+        entry(&mut text, "  ", option, 28, &t!(key));
+    }
+    text.trim_end().to_string()
+}
+
+/// Adds a line with `term` and its description in a column `width` wide
+/// after `lead`. Terms too long for the column get their description below.
+fn entry(text: &mut String, lead: &str, term: &str, width: usize, description: &str) {
+    let mut lines = wrap(description, DESCRIPTION_WIDTH).into_iter();
+    if term.chars().count() < width {
+        let first = lines.next().unwrap_or_default();
+        text.push_str(&format!("{}{:<width$} {}\n", lead, term, first, width = width));
+    } else {
+        text.push_str(&format!("{}{}\n", lead, term));
+    }
+    let column = " ".repeat(lead.chars().count() + width + 1);
+    for line in lines {
+        text.push_str(&format!("{}{}\n", column, line));
+    }
+}
+
+/// Breaks `text` between words into lines of at most `width` characters.
+/// A single word longer than that gets a line of its own.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+/// Pulls `--lang <code>` out of the arguments, wherever it appears, so it
+/// works with every subcommand. Returns the locale and the remaining arguments.
+pub fn take_lang(args: Vec<String>) -> Result<(Option<Locale>, Vec<String>), String> {
+    let mut locale = None;
+    let mut rest = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg != "--lang" {
+            rest.push(arg);
+            continue;
+        }
+        let code = next_value(&mut args, &arg)?;
+        let available: Vec<_> = Locale::ALL.iter().map(Locale::code).collect();
+        locale = Some(Locale::parse(&code).ok_or_else(|| {
+            t!("unsupported_language", code = code, available = available.join(", "))
+        })?);
+    }
+    Ok((locale, rest))
+}
+
+/// Parses the arguments that follow the program name.
+pub fn parse<I, S>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut player = None;
+    let mut seed = None;
+    let mut script = None;
+    let mut tui = false;
+    let mut resume = false;
+    let mut show_scores = false;
+    let mut config_args = Vec::new();
+
+    let mut args = args.into_iter().peekable();
+    let subcommand = match args.peek() {
+        Some(first) if !first.as_ref().starts_with("--") => args.next(),
+        _ => None,
+    };
+
+    match subcommand.as_ref().map(|s| s.as_ref()) {
+        None => {}
+        Some("reverse") => return Ok(Command::Reverse { config: Config::from_args(args)? }),
+        Some("bench") => return parse_bench(args),
+        Some("serve") => return parse_serve(args),
+        Some("join") => return parse_join(args),
+        Some("bulls") => return parse_bulls(args),
+        Some("replay") => return parse_replay(args),
+        Some(other) => return Err(t!("unknown_command", name = other)),
+    }
+
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        match arg {
+            "--scores" => show_scores = true,
+            "--name" => player = Some(next_value(&mut args, arg)?),
+            "--seed" => seed = Some(parse_seed(&next_value(&mut args, arg)?)?),
+            "--script" => script = Some(next_value(&mut args, arg)?),
+            "--tui" => tui = true,
+            "--resume" => resume = true,
+            _ => config_args.push(arg.to_string()),
+        }
+    }
+
+    if show_scores {
+        return Ok(Command::Scores);
+    }
+
+    Ok(Command::Play {
+        config: Config::from_args(config_args)?,
+        player: player.unwrap_or_else(default_player),
+        seed,
+        script,
+        tui,
+        resume,
+    })
+}
+
+fn parse_replay<I, S>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let log = args.next().ok_or_else(|| t!("replay_needs_log"))?.as_ref().to_string();
+    match args.next() {
+        Some(extra) => Err(t!("unexpected_argument", argument = extra.as_ref())),
+        None => Ok(Command::Replay { log }),
+    }
+}
+
+fn parse_bench<I, S>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut games = 10_000;
+    let mut seed = 0;
+    let mut strategy = None;
+    let mut config_args = Vec::new();
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        match arg {
+            "--games" => games = parse_number(&next_value(&mut args, arg)?, arg)? as usize,
+            "--seed" => seed = parse_seed(&next_value(&mut args, arg)?)?,
+            "--strategy" => {
+                let name = next_value(&mut args, arg)?;
+                if strategy::by_name(&name, 0).is_none() {
+                    return Err(t!("unknown_strategy", name = name));
+                }
+                strategy = Some(name);
+            }
+            _ => config_args.push(arg.to_string()),
+        }
+    }
+
+    Ok(Command::Bench { config: Config::from_args(config_args)?, games, seed, strategy })
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value.parse().map_err(|_| t!("invalid_seed", value = value))
+}
+
+fn parse_port(value: &str) -> Result<u16, String> {
+    value.parse().map_err(|_| t!("invalid_port", value = value))
+}
+
+fn parse_serve<I, S>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    // Anyone who can reach the port can play, so other machines are opt-in
+    let mut host = String::from("127.0.0.1");
+    let mut port = DEFAULT_PORT;
+    let mut mode = Mode::Race;
+    let mut config_args = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        match arg {
+            "--host" => host = next_value(&mut args, arg)?,
+            "--port" => port = parse_port(&next_value(&mut args, arg)?)?,
+            "--mode" => {
+                let name = next_value(&mut args, arg)?;
+                mode = Mode::parse(&name).ok_or_else(|| t!("unknown_mode", name = name))?;
+            }
+            _ => config_args.push(arg.to_string()),
+        }
+    }
+
+    Ok(Command::Serve { config: Config::from_args(config_args)?, host, port, mode })
+}
+
+fn parse_join<I, S>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut host = String::from("127.0.0.1");
+    let mut port = DEFAULT_PORT;
+    let mut player = None;
+
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        match arg {
+            "--host" => host = next_value(&mut args, arg)?,
+            "--port" => port = parse_port(&next_value(&mut args, arg)?)?,
+            "--name" => player = Some(next_value(&mut args, arg)?),
+            _ => return Err(t!("unknown_argument", argument = arg)),
+        }
+    }
+
+    let player = player.unwrap_or_else(default_player);
+    if player.split_whitespace().count() != 1 {
+        return Err(t!("single_word_name"));
+    }
+    Ok(Command::Join { host, port, player })
+}
+
+fn parse_bulls<I, S>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let defaults = Rules::default();
+    let mut length = defaults.length();
+    let mut alphabet = defaults.alphabet();
+    let mut duplicates = defaults.duplicates();
+    let mut attempts = defaults.max_attempts();
+    let mut solve = None;
+
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        match arg {
+            "--length" => length = parse_number(&next_value(&mut args, arg)?, arg)? as usize,
+            "--alphabet" => alphabet = next_value(&mut args, arg)?,
+            "--duplicates" => duplicates = true,
+            "--attempts" => attempts = Some(parse_number(&next_value(&mut args, arg)?, arg)?),
+            "--unlimited" => attempts = None,
+            "--solve" => solve = Some(next_value(&mut args, arg)?),
+            _ => return Err(t!("unknown_argument", argument = arg)),
+        }
+    }
+
+    let rules = Rules::new(length, &alphabet, duplicates, attempts)?;
+    if let Some(code) = &solve {
+        rules.parse(code)?;
+    }
+    Ok(Command::Bulls { rules, solve })
+}
+
+fn default_player() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| String::from("player"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_and_config_flags_mix() {
+        let command = parse(["--hard", "--name", "ann"]).unwrap();
+        assert_eq!(
+            command,
+            Command::Play {
+                config: Config::hard(),
+                player: String::from("ann"),
+                seed: None,
+                script: None,
+                tui: false,
+                resume: false,
+            }
+        );
+    }
+
+    #[test]
+    fn seed_and_script() {
+        let command = parse(["--script", "-", "--seed", "7"]).unwrap();
+        let Command::Play { seed, script, .. } = command else { panic!("{:?}", command) };
+        assert_eq!((seed, script.as_deref()), (Some(7), Some("-")));
+        assert!(parse(["--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn scores_view() {
+        assert_eq!(parse(["--scores"]), Ok(Command::Scores));
+        assert!(parse(["--name"]).is_err());
+    }
+
+    #[test]
+    fn subcommands() {
+        assert_eq!(parse(["reverse", "--easy"]), Ok(Command::Reverse { config: Config::easy() }));
+        assert_eq!(parse(["replay", "a.json"]), Ok(Command::Replay { log: String::from("a.json") }));
+        assert!(parse(["replay"]).is_err());
+        assert!(parse(["fly"]).is_err());
+    }
+
+    #[test]
+    fn lang_works_anywhere() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let (locale, rest) = take_lang(args(&["bench", "--lang", "tr", "--hard"])).unwrap();
+        assert_eq!(locale, Some(Locale::Tr));
+        assert_eq!(rest, args(&["bench", "--hard"]));
+        assert_eq!(take_lang(args(&["--easy"])), Ok((None, args(&["--easy"]))));
+        assert!(take_lang(args(&["--lang", "xx"])).is_err());
+        assert!(take_lang(args(&["--lang"])).is_err());
+    }
+
+    #[test]
+    fn bench_options() {
+        let command = parse(["bench", "--hard", "--games", "50", "--seed", "9", "--strategy", "linear"]);
+        assert_eq!(
+            command,
+            Ok(Command::Bench {
+                config: Config::hard(),
+                games: 50,
+                seed: 9,
+                strategy: Some(String::from("linear")),
+            })
+        );
+        assert!(parse(["bench", "--strategy", "psychic"]).is_err());
+    }
+
+    #[test]
+    fn serve_and_join_options() {
+        assert_eq!(
+            parse(["serve", "--mode", "turns", "--port", "9000", "--easy"]),
+            Ok(Command::Serve { config: Config::easy(), host: String::from("127.0.0.1"), port: 9000, mode: Mode::Turns })
+        );
+        assert_eq!(
+            parse(["serve", "--host", "0.0.0.0"]),
+            Ok(Command::Serve { config: Config::default(), host: String::from("0.0.0.0"), port: DEFAULT_PORT, mode: Mode::Race })
+        );
+        assert_eq!(
+            parse(["join", "--name", "ann"]),
+            Ok(Command::Join {
+                host: String::from("127.0.0.1"),
+                port: DEFAULT_PORT,
+                player: String::from("ann"),
+            })
+        );
+        assert!(parse(["join", "--name", "ann lee"]).is_err());
+        assert!(parse(["serve", "--port", "99999"]).is_err());
+    }
+
+    #[test]
+    fn bulls_options() {
+        let command = parse(["bulls", "--length", "3", "--alphabet", "abcdef", "--duplicates"]).unwrap();
+        let Command::Bulls { rules, solve: None } = command else { panic!("{:?}", command) };
+        assert_eq!((rules.length(), rules.alphabet(), rules.duplicates()), (3, String::from("abcdef"), true));
+
+        assert!(parse(["bulls", "--solve", "1234"]).is_ok());
+        assert!(parse(["bulls", "--solve", "1123"]).is_err());
+        assert!(parse(["bulls", "--length", "11"]).is_err());
+    }
+
+    #[test]
+    fn usage_wraps_descriptions_into_their_column() {
+        assert_eq!(wrap("a bb ccc dd", 6), ["a bb", "ccc dd"]);
+
+        let usage = usage();
+        assert!(usage.starts_with("Usage: guessing_game [options]"));
+        assert!(usage.contains("\n  --tui                        full-screen mode"));
+        // The --script description runs over several lines, all in its column
+        let script = usage.lines().skip_while(|line| !line.contains("--script")).nth(1).unwrap();
+        assert!(script.starts_with(&" ".repeat(31)) && !script.starts_with(&" ".repeat(32)));
+        assert!(usage.lines().all(|line| line.chars().count() <= 100), "{}", usage);
+    }
+}
@@ -0,0 +1,279 @@
+// config.rs
+// Difficulty levels and the command-line flags that select them.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hints::{HintCosts, HintSettings};
+use crate::t;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Custom,
+    ];
+
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        match name.to_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            "custom" => Some(Difficulty::Custom),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    pub difficulty: Difficulty,
+    pub min: u32,
+    pub max: u32,
+    /// `None` means unlimited guesses.
+    pub max_attempts: Option<u32>,
+    pub hints: HintSettings,
+}
+
+impl Config {
+    pub fn easy() -> Config {
+        Config {
+            difficulty: Difficulty::Easy,
+            min: 1,
+            max: 50,
+            max_attempts: Some(10),
+            // Cheap hints for beginners
+            hints: HintSettings {
+                proximity: true,
+                trend: true,
+                budget: 30,
+                costs: HintCosts { parity: 3, divisible_by: 5, digit_sum: 8 },
+            },
+        }
+    }
+
+    pub fn normal() -> Config {
+        Config {
+            difficulty: Difficulty::Normal,
+            min: 1,
+            max: 100,
+            max_attempts: Some(7),
+            hints: HintSettings { proximity: true, trend: true, budget: 20, costs: HintCosts::default() },
+        }
+    }
+
+    pub fn hard() -> Config {
+        Config {
+            difficulty: Difficulty::Hard,
+            min: 1,
+            max: 1000,
+            max_attempts: Some(10),
+            // Only the warmer/colder trend is free on hard, and points are scarce.
+            hints: HintSettings {
+                proximity: false,
+                trend: true,
+                budget: 10,
+                costs: HintCosts { parity: 6, divisible_by: 8, digit_sum: 10 },
+            },
+        }
+    }
+
+    /// A custom range. Fails if the bounds are reversed or no attempts are allowed.
+    pub fn custom(min: u32, max: u32, max_attempts: Option<u32>) -> Result<Config, String> {
+        if min > max {
+            return Err(t!("invalid_range", min = min, max = max));
+        }
+        if max_attempts == Some(0) {
+            return Err(t!("attempts_zero"));
+        }
+        Ok(Config {
+            difficulty: Difficulty::Custom,
+            min,
+            max,
+            max_attempts,
+            hints: HintSettings { proximity: true, trend: true, budget: 20, costs: HintCosts::default() },
+        })
+    }
+
+    pub fn for_difficulty(difficulty: Difficulty) -> Config {
+        match difficulty {
+            Difficulty::Easy => Config::easy(),
+            Difficulty::Hard => Config::hard(),
+            // A custom game without explicit bounds starts from the normal range, unlimited.
+            Difficulty::Custom => Config::custom(1, 100, None).expect("valid default range"),
+            Difficulty::Normal => Config::normal(),
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<u32> {
+        self.min..=self.max
+    }
+
+    /// The line shown before each guess, e.g. "Please input your guess (1-100):".
+    pub fn prompt(&self) -> String {
+        t!("prompt", min = self.min, max = self.max)
+    }
+
+    /// Builds a configuration from command-line flags (without the program name).
+    ///
+    /// Accepted flags:
+    ///   --easy | --normal | --hard        pick a preset
+    ///   --difficulty <name>               same, by name (easy, normal, hard, custom)
+    ///   --min <n> --max <n> --attempts <n> custom bounds; any of them switches to custom
+    ///   --no-hints                        turn every hint off
+    ///   --hint-budget <n>                 points available for buying hints
+    pub fn from_args<I, S>(args: I) -> Result<Config, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut difficulty = Difficulty::Normal;
+        let mut min = None;
+        let mut max = None;
+        let mut attempts = None;
+        let mut no_hints = false;
+        let mut hint_budget = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            match arg {
+                "--easy" => difficulty = Difficulty::Easy,
+                "--normal" => difficulty = Difficulty::Normal,
+                "--hard" => difficulty = Difficulty::Hard,
+                "--difficulty" => {
+                    let name = next_value(&mut args, arg)?;
+                    difficulty = Difficulty::parse(&name)
+                        .ok_or_else(|| t!("unknown_difficulty", name = name))?;
+                }
+                "--min" => min = Some(parse_number(&next_value(&mut args, arg)?, arg)?),
+                "--max" => max = Some(parse_number(&next_value(&mut args, arg)?, arg)?),
+                "--attempts" => attempts = Some(parse_number(&next_value(&mut args, arg)?, arg)?),
+                "--no-hints" => no_hints = true,
+                "--hint-budget" => hint_budget = Some(parse_number(&next_value(&mut args, arg)?, arg)?),
+                _ => return Err(t!("unknown_argument", argument = arg)),
+            }
+        }
+
+        let mut config = if min.is_none() && max.is_none() && attempts.is_none() {
+            Config::for_difficulty(difficulty)
+        } else {
+            let base = Config::for_difficulty(difficulty);
+            Config {
+                hints: base.hints,
+                ..Config::custom(
+                    min.unwrap_or(base.min),
+                    max.unwrap_or(base.max),
+                    attempts.or(base.max_attempts),
+                )?
+            }
+        };
+
+        if let Some(budget) = hint_budget {
+            config.hints.budget = budget;
+        }
+        if no_hints {
+            config.hints = HintSettings::off();
+        }
+        Ok(config)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::normal()
+    }
+}
+
+pub(crate) fn next_value<I, S>(args: &mut I, flag: &str) -> Result<String, String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.next()
+        .map(|value| value.as_ref().to_string())
+        .ok_or_else(|| t!("missing_value", flag = flag))
+}
+
+pub(crate) fn parse_number(value: &str, flag: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| t!("invalid_number_for", flag = flag, value = value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_flags_means_normal() {
+        let args: [&str; 0] = [];
+        assert_eq!(Config::from_args(args), Ok(Config::normal()));
+    }
+
+    #[test]
+    fn presets_by_flag_and_by_name() {
+        assert_eq!(Config::from_args(["--hard"]), Ok(Config::hard()));
+        assert_eq!(Config::from_args(["--difficulty", "Easy"]), Ok(Config::easy()));
+    }
+
+    #[test]
+    fn bounds_switch_to_custom() {
+        let config = Config::from_args(["--min", "10", "--max", "20", "--attempts", "3"]).unwrap();
+
+        assert_eq!(config.difficulty, Difficulty::Custom);
+        assert_eq!(config.range(), 10..=20);
+        assert_eq!(config.max_attempts, Some(3));
+        assert_eq!(config.prompt(), "Please input your guess (10-20):");
+    }
+
+    #[test]
+    fn bad_flags_are_reported() {
+        assert!(Config::from_args(["--min", "50", "--max", "10"]).is_err());
+        assert!(Config::from_args(["--attempts", "0"]).is_err());
+        assert!(Config::from_args(["--max"]).is_err());
+        assert!(Config::from_args(["--max", "lots"]).is_err());
+        assert!(Config::from_args(["--difficulty", "nightmare"]).is_err());
+        assert!(Config::from_args(["--cheat"]).is_err());
+    }
+
+    #[test]
+    fn hint_flags() {
+        let config = Config::from_args(["--hard", "--hint-budget", "50"]).unwrap();
+        assert_eq!(config.difficulty, Difficulty::Hard);
+        assert_eq!(config.hints.budget, 50);
+        assert!(!config.hints.proximity);
+        assert_eq!(config.hints.costs, Config::hard().hints.costs);
+        assert!(Config::easy().hints.costs.parity < config.hints.costs.parity);
+
+        let config = Config::from_args(["--easy", "--no-hints"]).unwrap();
+        assert_eq!(config.hints, HintSettings::off());
+    }
+}
@@ -0,0 +1,273 @@
+// game.rs
+// The rules of the guessing game as a small state machine, free of any I/O.
+
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// Source of secret numbers. Anything implementing `rand::Rng` works,
+/// and tests can plug in a fixed value instead.
+pub trait Rng {
+    fn pick(&mut self, range: RangeInclusive<u32>) -> u32;
+}
+
+impl<R: rand::Rng> Rng for R {
+    fn pick(&mut self, range: RangeInclusive<u32>) -> u32 {
+        self.gen_range(range)
+    }
+}
+
+/// An `Rng` that always hands out the same number. Handy for tests and demos.
+#[derive(Debug, Clone, Copy)]
+pub struct Fixed(pub u32);
+
+impl Rng for Fixed {
+    fn pick(&mut self, _range: RangeInclusive<u32>) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    AwaitingGuess,
+    Won,
+    OutOfAttempts,
+}
+
+/// What the game answers to a single guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feedback {
+    TooSmall,
+    TooBig,
+    Correct,
+    /// The guess lies outside the game's range and was not counted.
+    OutOfRange,
+    /// The game already ended; the guess was ignored.
+    GameOver,
+}
+
+impl From<Ordering> for Feedback {
+    fn from(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Less => Feedback::TooSmall,
+            Ordering::Greater => Feedback::TooBig,
+            Ordering::Equal => Feedback::Correct,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    secret: u32,
+    range: RangeInclusive<u32>,
+    max_attempts: Option<u32>,
+    attempts: u32,
+    state: GameState,
+    /// Every counted guess with its feedback, oldest first.
+    history: Vec<(u32, Feedback)>,
+}
+
+impl Game {
+    /// Starts a game with a secret drawn from `range`.
+    /// `max_attempts` of `None` means the player may guess forever.
+    pub fn new<R: Rng + ?Sized>(
+        range: RangeInclusive<u32>,
+        max_attempts: Option<u32>,
+        rng: &mut R,
+    ) -> Game {
+        let secret = rng.pick(range.clone());
+        Game {
+            secret,
+            range,
+            max_attempts,
+            attempts: 0,
+            state: GameState::AwaitingGuess,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn from_config<R: Rng + ?Sized>(config: &Config, rng: &mut R) -> Game {
+        Game::new(config.range(), config.max_attempts, rng)
+    }
+
+    /// Rebuilds a game by playing `guesses` against a known secret.
+    pub fn replay(config: &Config, secret: u32, guesses: &[u32]) -> Game {
+        let mut game = Game::from_config(config, &mut Fixed(secret));
+        for &guess in guesses {
+            game.guess(guess);
+        }
+        game
+    }
+
+    pub fn guess(&mut self, guess: u32) -> Feedback {
+        if self.state != GameState::AwaitingGuess {
+            return Feedback::GameOver;
+        }
+        if !self.range.contains(&guess) {
+            return Feedback::OutOfRange;
+        }
+
+        self.attempts += 1;
+        let feedback = Feedback::from(guess.cmp(&self.secret));
+        self.history.push((guess, feedback));
+
+        if feedback == Feedback::Correct {
+            self.state = GameState::Won;
+        } else if self.attempts_left() == Some(0) {
+            self.state = GameState::OutOfAttempts;
+        }
+
+        feedback
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.state != GameState::AwaitingGuess
+    }
+
+    pub fn range(&self) -> RangeInclusive<u32> {
+        self.range.clone()
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max.saturating_sub(self.attempts))
+    }
+
+    pub fn history(&self) -> &[(u32, Feedback)] {
+        &self.history
+    }
+
+    /// The numbers that are still possible given the feedback so far.
+    pub fn feasible(&self) -> RangeInclusive<u32> {
+        let mut low = *self.range.start();
+        let mut high = *self.range.end();
+        for &(guess, feedback) in &self.history {
+            match feedback {
+                Feedback::TooSmall => low = low.max(guess + 1),
+                Feedback::TooBig => high = high.min(guess - 1),
+                Feedback::Correct => return guess..=guess,
+                _ => {}
+            }
+        }
+        low..=high
+    }
+
+    /// The secret number. Only meant to be revealed once the game is over.
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feedback_follows_ordering() {
+        let mut game = Game::new(1..=100, None, &mut Fixed(42));
+
+        assert_eq!(game.guess(10), Feedback::TooSmall);
+        assert_eq!(game.guess(90), Feedback::TooBig);
+        assert_eq!(game.state(), GameState::AwaitingGuess);
+        assert_eq!(game.guess(42), Feedback::Correct);
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(game.attempts(), 3);
+    }
+
+    #[test]
+    fn guesses_after_the_end_are_ignored() {
+        let mut game = Game::new(1..=100, None, &mut Fixed(7));
+        game.guess(7);
+
+        assert_eq!(game.guess(7), Feedback::GameOver);
+        assert_eq!(game.attempts(), 1);
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let mut game = Game::new(1..=100, Some(2), &mut Fixed(50));
+
+        assert_eq!(game.guess(1), Feedback::TooSmall);
+        assert_eq!(game.attempts_left(), Some(1));
+        assert_eq!(game.guess(99), Feedback::TooBig);
+        assert_eq!(game.state(), GameState::OutOfAttempts);
+        assert_eq!(game.guess(50), Feedback::GameOver);
+    }
+
+    #[test]
+    fn winning_on_the_last_attempt_counts() {
+        let mut game = Game::new(1..=100, Some(1), &mut Fixed(50));
+
+        assert_eq!(game.guess(50), Feedback::Correct);
+        assert_eq!(game.state(), GameState::Won);
+    }
+
+    #[test]
+    fn history_narrows_the_feasible_range() {
+        let mut game = Game::new(1..=100, None, &mut Fixed(42));
+        assert_eq!(game.feasible(), 1..=100);
+
+        game.guess(20);
+        game.guess(300);
+        game.guess(60);
+        assert_eq!(game.history(), &[(20, Feedback::TooSmall), (60, Feedback::TooBig)]);
+        assert_eq!(game.feasible(), 21..=59);
+
+        game.guess(42);
+        assert_eq!(game.feasible(), 42..=42);
+    }
+
+    #[test]
+    fn replay_rebuilds_the_same_game() {
+        let config = Config::custom(1, 100, Some(3)).unwrap();
+        let game = Game::replay(&config, 42, &[50, 20, 42]);
+
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(game.attempts(), 3);
+        assert_eq!(game.history()[1], (20, Feedback::TooSmall));
+    }
+
+    #[test]
+    fn out_of_range_guesses_do_not_cost_an_attempt() {
+        let mut game = Game::new(1..=10, Some(3), &mut Fixed(5));
+
+        assert_eq!(game.guess(0), Feedback::OutOfRange);
+        assert_eq!(game.guess(11), Feedback::OutOfRange);
+        assert_eq!(game.attempts(), 0);
+        assert_eq!(game.attempts_left(), Some(3));
+    }
+
+    #[test]
+    fn from_config_uses_range_and_limit() {
+        let config = Config::custom(5, 6, Some(4)).unwrap();
+        let game = Game::from_config(&config, &mut rand::thread_rng());
+
+        assert_eq!(game.range(), 5..=6);
+        assert_eq!(game.max_attempts(), Some(4));
+        assert!(game.range().contains(&game.secret()));
+    }
+
+    #[test]
+    fn real_rng_stays_in_range() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let game = Game::new(1..=10, None, &mut rng);
+            assert!((1..=10).contains(&game.secret()));
+        }
+    }
+}
@@ -0,0 +1,354 @@
+// hints.rs
+// Optional help for the player: hot/cold proximity, warmer/colder trends,
+// and hints that can be bought with points.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Game;
+use crate::t;
+
+/// How close a guess is, relative to the size of the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Temperature {
+    Freezing,
+    Cold,
+    Cool,
+    Warm,
+    Hot,
+    Burning,
+}
+
+impl Temperature {
+    pub fn of(guess: u32, secret: u32, range_size: u64) -> Temperature {
+        let fraction = guess.abs_diff(secret) as f64 / range_size.max(1) as f64;
+        match fraction {
+            f if f <= 0.02 => Temperature::Burning,
+            f if f <= 0.05 => Temperature::Hot,
+            f if f <= 0.10 => Temperature::Warm,
+            f if f <= 0.20 => Temperature::Cool,
+            f if f <= 0.40 => Temperature::Cold,
+            _ => Temperature::Freezing,
+        }
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = match self {
+            Temperature::Freezing => "temperature_freezing",
+            Temperature::Cold => "temperature_cold",
+            Temperature::Cool => "temperature_cool",
+            Temperature::Warm => "temperature_warm",
+            Temperature::Hot => "temperature_hot",
+            Temperature::Burning => "temperature_burning",
+        };
+        write!(f, "{}", t!(key))
+    }
+}
+
+/// How a guess compares to the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Warmer,
+    Colder,
+    Same,
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = match self {
+            Trend::Warmer => "trend_warmer",
+            Trend::Colder => "trend_colder",
+            Trend::Same => "trend_same",
+        };
+        write!(f, "{}", t!(key))
+    }
+}
+
+/// Hints that cost points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintKind {
+    Parity,
+    DivisibleBy(u32),
+    DigitSum,
+}
+
+impl HintKind {
+    /// Parses what the player types after "hint": "parity", "div <n>" or "digits".
+    pub fn parse(input: &str) -> Option<HintKind> {
+        let mut words = input.split_whitespace();
+        let kind = match words.next()?.to_lowercase().as_str() {
+            "parity" => HintKind::Parity,
+            "digits" => HintKind::DigitSum,
+            "div" => match words.next()?.parse() {
+                Ok(0) | Err(_) => return None,
+                Ok(n) => HintKind::DivisibleBy(n),
+            },
+            _ => return None,
+        };
+        if words.next().is_some() { None } else { Some(kind) }
+    }
+}
+
+/// What a bought hint reveals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Revealed {
+    Even(bool),
+    DivisibleBy { divisor: u32, divisible: bool },
+    DigitSum(u32),
+}
+
+impl fmt::Display for Revealed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            Revealed::Even(true) => t!("revealed_even"),
+            Revealed::Even(false) => t!("revealed_odd"),
+            Revealed::DivisibleBy { divisor, divisible: true } => t!("revealed_divisible", divisor = divisor),
+            Revealed::DivisibleBy { divisor, divisible: false } => {
+                t!("revealed_not_divisible", divisor = divisor)
+            }
+            Revealed::DigitSum(sum) => t!("revealed_digit_sum", sum = sum),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintError {
+    /// Bought hints are switched off for this difficulty.
+    Disabled,
+    NotEnoughPoints { cost: u32, points: u32 },
+}
+
+impl fmt::Display for HintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            HintError::Disabled => t!("hint_disabled"),
+            HintError::NotEnoughPoints { cost, points } => t!("hint_too_expensive", cost = cost, points = points),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+/// What each bought hint costs, in points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintCosts {
+    pub parity: u32,
+    pub divisible_by: u32,
+    pub digit_sum: u32,
+}
+
+impl HintCosts {
+    pub fn of(&self, kind: HintKind) -> u32 {
+        match kind {
+            HintKind::Parity => self.parity,
+            HintKind::DivisibleBy(_) => self.divisible_by,
+            HintKind::DigitSum => self.digit_sum,
+        }
+    }
+}
+
+/// The prices on normal difficulty; also what games saved before hints had
+/// prices of their own are loaded with.
+impl Default for HintCosts {
+    fn default() -> HintCosts {
+        HintCosts { parity: 5, divisible_by: 8, digit_sum: 10 }
+    }
+}
+
+/// Which hints a difficulty offers and what they cost. A `budget` of 0
+/// disables bought hints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintSettings {
+    pub proximity: bool,
+    pub trend: bool,
+    pub budget: u32,
+    #[serde(default)]
+    pub costs: HintCosts,
+}
+
+impl HintSettings {
+    pub fn off() -> HintSettings {
+        HintSettings { proximity: false, trend: false, budget: 0, costs: HintCosts::default() }
+    }
+}
+
+/// Free hints shown after a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuessHints {
+    pub temperature: Option<Temperature>,
+    pub trend: Option<Trend>,
+}
+
+impl GuessHints {
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none() && self.trend.is_none()
+    }
+}
+
+impl fmt::Display for GuessHints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match (self.temperature, self.trend) {
+            (Some(temperature), Some(trend)) => t!("free_hints_both", temperature = temperature, trend = trend),
+            (Some(temperature), None) => t!("free_hints_temperature", temperature = temperature),
+            (None, Some(trend)) => t!("free_hints_trend", trend = trend),
+            (None, None) => String::new(),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+/// Hint state for one game: the point balance and the previous guess.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hints {
+    settings: HintSettings,
+    points: u32,
+    last_distance: Option<u32>,
+}
+
+impl Hints {
+    pub fn new(settings: HintSettings) -> Hints {
+        Hints { settings, points: settings.budget, last_distance: None }
+    }
+
+    /// Points left; this is the player's score at the end of the game.
+    pub fn points(&self) -> u32 {
+        self.points
+    }
+
+    /// The free hints for a guess that `game` just answered.
+    pub fn after_guess(&mut self, guess: u32, game: &Game) -> GuessHints {
+        let range = game.range();
+        let range_size = (*range.end() - *range.start()) as u64 + 1;
+        let distance = guess.abs_diff(game.secret());
+
+        let temperature = self
+            .settings
+            .proximity
+            .then(|| Temperature::of(guess, game.secret(), range_size));
+        let trend = match self.last_distance {
+            Some(last) if self.settings.trend => Some(match distance.cmp(&last) {
+                std::cmp::Ordering::Less => Trend::Warmer,
+                std::cmp::Ordering::Greater => Trend::Colder,
+                std::cmp::Ordering::Equal => Trend::Same,
+            }),
+            _ => None,
+        };
+
+        self.last_distance = Some(distance);
+        GuessHints { temperature, trend }
+    }
+
+    pub fn buy(&mut self, kind: HintKind, game: &Game) -> Result<Revealed, HintError> {
+        if self.settings.budget == 0 {
+            return Err(HintError::Disabled);
+        }
+        let cost = self.settings.costs.of(kind);
+        if cost > self.points {
+            return Err(HintError::NotEnoughPoints { cost, points: self.points });
+        }
+        self.points -= cost;
+
+        let secret = game.secret();
+        Ok(match kind {
+            HintKind::Parity => Revealed::Even(secret.is_multiple_of(2)),
+            HintKind::DivisibleBy(divisor) => {
+                Revealed::DivisibleBy { divisor, divisible: secret.is_multiple_of(divisor) }
+            }
+            HintKind::DigitSum => Revealed::DigitSum(digit_sum(secret)),
+        })
+    }
+}
+
+fn digit_sum(mut n: u32) -> u32 {
+    let mut sum = 0;
+    while n > 0 {
+        sum += n % 10;
+        n /= 10;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Fixed;
+
+    fn all_on(budget: u32) -> HintSettings {
+        HintSettings { proximity: true, trend: true, budget, costs: HintCosts::default() }
+    }
+
+    #[test]
+    fn temperature_scales_with_the_range() {
+        assert_eq!(Temperature::of(50, 51, 100), Temperature::Burning);
+        assert_eq!(Temperature::of(50, 60, 100), Temperature::Warm);
+        assert_eq!(Temperature::of(1, 100, 100), Temperature::Freezing);
+        // The same distance is much warmer in a bigger range.
+        assert_eq!(Temperature::of(50, 60, 1000), Temperature::Burning);
+    }
+
+    #[test]
+    fn trend_compares_with_the_previous_guess() {
+        let game = Game::new(1..=100, None, &mut Fixed(50));
+        let mut hints = Hints::new(all_on(0));
+
+        assert_eq!(hints.after_guess(10, &game).trend, None);
+        assert_eq!(hints.after_guess(30, &game).trend, Some(Trend::Warmer));
+        assert_eq!(hints.after_guess(90, &game).trend, Some(Trend::Colder));
+        assert_eq!(hints.after_guess(10, &game).trend, Some(Trend::Same));
+    }
+
+    #[test]
+    fn disabled_free_hints_stay_quiet() {
+        let game = Game::new(1..=100, None, &mut Fixed(50));
+        let mut hints = Hints::new(HintSettings::off());
+        hints.after_guess(10, &game);
+
+        assert_eq!(hints.after_guess(40, &game), GuessHints { temperature: None, trend: None });
+        assert_eq!(hints.buy(HintKind::Parity, &game), Err(HintError::Disabled));
+    }
+
+    #[test]
+    fn bought_hints_cost_points() {
+        let game = Game::new(1..=100, None, &mut Fixed(84));
+        let mut hints = Hints::new(all_on(20));
+
+        assert_eq!(hints.buy(HintKind::Parity, &game), Ok(Revealed::Even(true)));
+        assert_eq!(
+            hints.buy(HintKind::DivisibleBy(7), &game),
+            Ok(Revealed::DivisibleBy { divisor: 7, divisible: true })
+        );
+        assert_eq!(hints.points(), 7);
+        assert_eq!(
+            hints.buy(HintKind::DigitSum, &game),
+            Err(HintError::NotEnoughPoints { cost: 10, points: 7 })
+        );
+        assert_eq!(hints.points(), 7);
+    }
+
+    #[test]
+    fn costs_come_from_the_settings() {
+        let game = Game::new(1..=100, None, &mut Fixed(84));
+        let costs = HintCosts { parity: 1, divisible_by: 2, digit_sum: 3 };
+        let mut hints = Hints::new(HintSettings { costs, ..all_on(6) });
+
+        hints.buy(HintKind::DigitSum, &game).unwrap();
+        hints.buy(HintKind::DivisibleBy(4), &game).unwrap();
+        assert_eq!(hints.points(), 1);
+        assert_eq!(hints.buy(HintKind::Parity, &game), Ok(Revealed::Even(true)));
+        assert_eq!(hints.points(), 0);
+    }
+
+    #[test]
+    fn hint_commands_parse() {
+        assert_eq!(HintKind::parse("parity"), Some(HintKind::Parity));
+        assert_eq!(HintKind::parse("div 3"), Some(HintKind::DivisibleBy(3)));
+        assert_eq!(HintKind::parse("DIGITS"), Some(HintKind::DigitSum));
+        assert_eq!(HintKind::parse("div 0"), None);
+        assert_eq!(HintKind::parse("div"), None);
+        assert_eq!(HintKind::parse("parity please"), None);
+        assert_eq!(digit_sum(907), 16);
+    }
+}
@@ -0,0 +1,274 @@
+// i18n.rs
+// Translated messages. Each locale has a catalog file under `locales/` that
+// is compiled into the binary; the `t!` and `tn!` macros look messages up.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{self, Display};
+use std::sync::OnceLock;
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    En,
+    Tr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Tr];
+
+    /// Accepts plain codes ("tr") as well as `LANG`-style values ("tr_TR.UTF-8").
+    pub fn parse(value: &str) -> Option<Locale> {
+        let language = value.split(['_', '-', '.', '@']).next()?.to_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "tr" => Some(Locale::Tr),
+            _ => None,
+        }
+    }
+
+    /// The locale asked for by `LC_ALL`, `LC_MESSAGES` or `LANG`, in that order.
+    pub fn from_env() -> Option<Locale> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::parse(&value))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Tr => "tr",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.txt"),
+            Locale::Tr => include_str!("../locales/tr.txt"),
+        }
+    }
+
+    /// The CLDR plural category of `n`. English and Turkish both only
+    /// tell one apart from everything else.
+    pub fn plural(&self, n: u64) -> Plural {
+        match self {
+            Locale::En | Locale::Tr => {
+                if n == 1 { Plural::One } else { Plural::Other }
+            }
+        }
+    }
+
+    /// The plural categories this locale's catalog must provide.
+    pub fn plural_forms(&self) -> &'static [Plural] {
+        match self {
+            Locale::En | Locale::Tr => &[Plural::One, Plural::Other],
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plural {
+    One,
+    Other,
+}
+
+impl Plural {
+    fn suffix(&self) -> &'static str {
+        match self {
+            Plural::One => "one",
+            Plural::Other => "other",
+        }
+    }
+}
+
+/// The messages of one locale, falling back to English for anything missing.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    locale: Locale,
+    messages: HashMap<String, String>,
+    fallback: Option<Box<Catalog>>,
+}
+
+impl Catalog {
+    pub fn load(locale: Locale) -> Catalog {
+        let fallback = (locale != Locale::En).then(|| Box::new(Catalog::load(Locale::En)));
+        Catalog { locale, messages: parse(locale.source()), fallback }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// The message for `key` with every `{name}` replaced by its value.
+    /// Unknown keys come back as the key itself, so a gap is visible but harmless.
+    pub fn text(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        match self.lookup(key) {
+            Some(template) => fill(template, args),
+            None => key.to_string(),
+        }
+    }
+
+    /// Like `text`, but picks the plural form for `count`, which is also
+    /// available to the message as `{count}`.
+    pub fn count(&self, key: &str, count: u64, args: &[(&str, &dyn Display)]) -> String {
+        let form = format!("{}.{}", key, self.locale.plural(count).suffix());
+        let mut args = args.to_vec();
+        args.push(("count", &count));
+        self.text(&form, &args)
+    }
+
+    fn lookup(&self, key: &str) -> Option<&str> {
+        match self.messages.get(key) {
+            Some(message) => Some(message),
+            None => self.fallback.as_ref()?.lookup(key),
+        }
+    }
+}
+
+/// Chooses the catalog for the rest of the program. Only the first call counts.
+pub fn init(locale: Locale) {
+    let _ = CATALOG.set(Catalog::load(locale));
+}
+
+/// The catalog picked by `init`, or English if nobody picked one.
+pub fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| Catalog::load(Locale::En))
+}
+
+/// Reads `key = message` lines. Blank lines and lines starting with `#` are skipped.
+fn parse(source: &str) -> HashMap<String, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, message)| (key.trim().to_string(), message.trim().to_string()))
+        .collect()
+}
+
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut message = template.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+/// Looks up a message in the current catalog: `t!("too_small")` or
+/// `t!("you_win", secret = 42)`.
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::catalog().text($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::catalog().text($key, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+])
+    };
+}
+
+/// Looks up a plural message: `tn!("attempts_left", 3)`.
+#[macro_export]
+macro_rules! tn {
+    ($key:expr, $count:expr) => {
+        $crate::i18n::catalog().count($key, $count as u64, &[])
+    };
+    ($key:expr, $count:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::catalog().count(
+            $key,
+            $count as u64,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(message: &str) -> BTreeSet<&str> {
+        message
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}'))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Keys without their plural suffix, so "attempts_left.one" counts as "attempts_left".
+    fn base_keys(locale: Locale) -> BTreeSet<String> {
+        parse(locale.source())
+            .into_keys()
+            .map(|key| match key.rsplit_once('.') {
+                Some((base, "one" | "other")) => base.to_string(),
+                _ => key,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_catalog_has_every_key() {
+        let english = base_keys(Locale::En);
+        for locale in Locale::ALL {
+            let keys = base_keys(locale);
+            let missing: Vec<_> = english.difference(&keys).collect();
+            let extra: Vec<_> = keys.difference(&english).collect();
+            assert!(missing.is_empty(), "{} is missing {:?}", locale, missing);
+            assert!(extra.is_empty(), "{} has unknown keys {:?}", locale, extra);
+        }
+    }
+
+    #[test]
+    fn plural_messages_have_every_form_and_matching_placeholders() {
+        let english = parse(Locale::En.source());
+        for locale in Locale::ALL {
+            let messages = parse(locale.source());
+            for (key, message) in &messages {
+                if let Some((base, "one" | "other")) = key.rsplit_once('.') {
+                    for form in locale.plural_forms() {
+                        let form = format!("{}.{}", base, form.suffix());
+                        assert!(messages.contains_key(&form), "{} is missing {}", locale, form);
+                    }
+                    continue;
+                }
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(&english[key]),
+                    "{}: placeholders of {} differ from English",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn locales_parse_from_lang_values() {
+        assert_eq!(Locale::parse("tr_TR.UTF-8"), Some(Locale::Tr));
+        assert_eq!(Locale::parse("en-GB"), Some(Locale::En));
+        assert_eq!(Locale::parse("TR"), Some(Locale::Tr));
+        assert_eq!(Locale::parse("C"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn messages_fill_placeholders_and_pick_plural_forms() {
+        let english = Catalog::load(Locale::En);
+        assert_eq!(english.count("attempts_left", 1, &[]), "Attempts left: 1 (last chance!)");
+        assert_eq!(english.count("attempts_left", 3, &[]), "Attempts left: 3");
+        assert_eq!(english.text("you_win", &[("secret", &42)]), "🎉 You win! The number was 42!");
+
+        let turkish = Catalog::load(Locale::Tr);
+        assert_eq!(turkish.text("too_small", &[]), "📉 Çok küçük!");
+        assert_eq!(turkish.text("no_such_key", &[]), "no_such_key");
+    }
+}
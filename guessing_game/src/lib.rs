@@ -0,0 +1,20 @@
+// lib.rs
+// Game rules for the guessing game. The binary in main.rs is only the I/O shell.
+
+pub mod bench;
+pub mod bulls_cows;
+pub mod cli;
+pub mod config;
+pub mod game;
+pub mod hints;
+pub mod i18n;
+pub mod multiplayer;
+pub mod reverse;
+pub mod save;
+pub mod scores;
+pub mod script;
+pub mod strategy;
+pub mod tui;
+
+pub use config::{Config, Difficulty};
+pub use game::{Feedback, Fixed, Game, GameState, Rng};
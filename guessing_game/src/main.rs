@@ -1,38 +1,665 @@
-use std::io;
-use std::cmp::Ordering;
-use rand::Rng;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+use std::process;
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use guessing_game::bench;
+use guessing_game::bulls_cows::{CodeGame, Outcome, Rules, Solver};
+use guessing_game::cli::{self, Command};
+use guessing_game::hints::{HintKind, Hints};
+use guessing_game::i18n::{self, Locale};
+use guessing_game::multiplayer::{self, Client, ClientMessage, Mode, Room, ServerMessage};
+use guessing_game::reverse::{self, Answer, Guesser, Step};
+use guessing_game::save::{self, SavedGame};
+use guessing_game::scores::{self, Record, ScoreStore};
+use guessing_game::script;
+use guessing_game::strategy;
+use guessing_game::tui::{self, Ending};
+use guessing_game::{t, tn, Config, Difficulty, Feedback, Game, GameState};
 
 fn main() {
-    println!("🎯 Guess the number!");
-    
-    let secret_number = rand::thread_rng().gen_range(1..=100);
-    
+    let (locale, args) = match cli::take_lang(env::args().skip(1).collect()) {
+        Ok(split) => split,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(2);
+        }
+    };
+    i18n::init(locale.or_else(Locale::from_env).unwrap_or(Locale::En));
+
+    let command = match cli::parse(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            eprintln!("{}", cli::usage());
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Play { config, seed, script: Some(path), .. } => run_script(&config, seed, &path),
+        // Piped guesses come from a program, not a player: no scores, saves or logs
+        Command::Play { resume: true, .. } if !io::stdin().is_terminal() => {
+            eprintln!("{}", t!("resume_needs_terminal"));
+            process::exit(2);
+        }
+        Command::Play { config, seed, .. } if !io::stdin().is_terminal() => run_script(&config, seed, "-"),
+        Command::Play { config, player, seed, script: None, tui, resume: resuming } => {
+            let terminal = io::stdin().is_terminal() && io::stdout().is_terminal();
+            if tui && !terminal {
+                eprintln!("{}", t!("tui_needs_terminal"));
+            }
+            if resuming {
+                resume(tui && terminal)
+            } else {
+                play(&config, &player, seed, tui && terminal)
+            }
+        }
+        Command::Scores => show_scores(),
+        Command::Reverse { config } => play_reverse(&config),
+        Command::Bench { config, games, seed, strategy } => {
+            run_bench(&config, games, seed, strategy.as_deref())
+        }
+        Command::Serve { config, host, port, mode } => serve(config, &host, port, mode),
+        Command::Join { host, port, player } => join(&host, port, &player),
+        Command::Bulls { rules, solve: None } => play_bulls(rules),
+        Command::Bulls { rules, solve: Some(code) } => solve_bulls(rules, &code),
+        Command::Replay { log } => replay(&log),
+    }
+}
+
+/// Reads one line from stdin, or `None` once input is exhausted.
+fn read_line() -> Option<String> {
+    let mut line = String::new();
+
+    let bytes = io::stdin()
+        .read_line(&mut line)
+        .expect("Failed to read line");
+
+    if bytes == 0 { None } else { Some(line) }
+}
+
+/// Prompts until `parse` accepts a line, printing its complaint each time.
+/// `None` once input runs out.
+fn read_valid<T>(prompt: &str, mut parse: impl FnMut(&str) -> Result<T, String>) -> Option<T> {
     loop {
-        println!("Please input your guess (1-100):");
-        
-        let mut guess = String::new();
-        
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
-        
+        println!("{}", prompt);
+        let line = read_line()?;
+        match parse(line.trim()) {
+            Ok(value) => return Some(value),
+            Err(e) => println!("❌ {}!", e),
+        }
+    }
+}
+
+/// The secret source for a game: reproducible with `--seed`, random otherwise.
+fn secret_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+fn run_script(config: &Config, seed: Option<u64>, path: &str) {
+    let mut game = Game::from_config(config, &mut secret_rng(seed));
+    let stdout = io::stdout().lock();
+
+    let result = if path == "-" {
+        script::run(&mut game, io::stdin().lock(), stdout)
+    } else {
+        match File::open(path) {
+            Ok(file) => script::run(&mut game, io::BufReader::new(file), stdout),
+            Err(e) => {
+                eprintln!("{}", t!("script_unreadable", path = path, error = e));
+                process::exit(2);
+            }
+        }
+    };
+
+    match result {
+        Ok(outcome) => process::exit(outcome.exit_code()),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(2);
+        }
+    }
+}
+
+fn play(config: &Config, player: &str, seed: Option<u64>, full_screen: bool) {
+    if save::save_path().is_ok_and(|path| path.exists()) {
+        println!("{}", t!("replace_unfinished"));
+    }
+    let game = Game::from_config(config, &mut secret_rng(seed));
+    let hints = Hints::new(config.hints);
+    run_game(config, player, game, hints, Duration::ZERO, full_screen);
+}
+
+fn resume(full_screen: bool) {
+    let saved = match save::save_path().and_then(|path| SavedGame::load(&path)) {
+        Ok(saved) => saved,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            eprintln!("{}", t!("nothing_to_resume"));
+            process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("{}", t!("save_unreadable", error = e));
+            process::exit(2);
+        }
+    };
+
+    let game = saved.game();
+    println!(
+        "{}",
+        tn!("resuming", game.attempts(), player = saved.player, difficulty = saved.config.difficulty)
+    );
+    for &(guess, feedback) in game.history() {
+        println!("   {} {}", guess, verdict(feedback));
+    }
+    let feasible = game.feasible();
+    println!("{}", t!("feasible_between", low = feasible.start(), high = feasible.end()));
+
+    run_game(&saved.config, &saved.player, game, saved.hints.clone(), saved.elapsed(), full_screen);
+}
+
+/// Plays `game` to the end, saving it after every move so it can be resumed.
+fn run_game(config: &Config, player: &str, mut game: Game, mut hints: Hints, elapsed: Duration, full_screen: bool) {
+    let started = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+    let checkpoint = |game: &Game, hints: &Hints| {
+        let saved = SavedGame::capture(player, config, game, hints, started.elapsed());
+        if let Err(e) = save::save_path().and_then(|path| saved.write(&path)) {
+            eprintln!("{}", t!("game_not_saved", error = e));
+        }
+    };
+
+    let finished = if full_screen {
+        match tui::play(config, &mut game, &mut hints, started, &checkpoint) {
+            Ok(ending) => ending == Ending::Finished,
+            Err(e) => {
+                eprintln!("{}", t!("terminal_error", error = e));
+                false
+            }
+        }
+    } else {
+        play_lines(config, &mut game, &mut hints, &checkpoint)
+    };
+
+    if !finished {
+        checkpoint(&game, &hints);
+        println!("{}", t!("game_saved"));
+        return;
+    }
+
+    match game.state() {
+        GameState::Won if config.hints.budget > 0 => println!("{}", tn!("score", hints.points())),
+        GameState::OutOfAttempts => println!("{}", t!("out_of_attempts", secret = game.secret())),
+        _ => {}
+    }
+
+    let record = Record::new(
+        player,
+        config.difficulty,
+        game.state() == GameState::Won,
+        game.attempts(),
+        started.elapsed().as_millis() as u64,
+    );
+    if let Err(e) = ScoreStore::open_default().and_then(|store| store.append(record)) {
+        eprintln!("{}", t!("score_not_saved", error = e));
+    }
+
+    let log = SavedGame::capture(player, config, &game, &hints, started.elapsed());
+    let logged = save::log_dir().map(|dir| dir.join(log.log_name())).and_then(|path| {
+        log.write(&path)?;
+        Ok(path)
+    });
+    match logged {
+        Ok(path) => println!("{}", t!("log_written", path = path.display())),
+        Err(e) => eprintln!("{}", t!("log_not_written", error = e)),
+    }
+    if let Ok(path) = save::save_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn replay(path: &str) {
+    let log = match SavedGame::load(Path::new(path)) {
+        Ok(log) => log,
+        Err(e) => {
+            eprintln!("{}", t!("replay_unreadable", path = path, error = e));
+            process::exit(2);
+        }
+    };
+    let config = &log.config;
+    let game = log.game();
+    let step = io::stdin().is_terminal();
+
+    println!(
+        "{}",
+        tn!(
+            "replay_header",
+            game.attempts(),
+            player = log.player,
+            difficulty = config.difficulty,
+            min = config.min,
+            max = config.max,
+            time = tui::clock(log.elapsed()),
+        )
+    );
+    println!("   [{}]  {}-{}", tui::range_bar(config.min, config.max, config.min, config.max, 40), config.min, config.max);
+
+    for turn in 1..=log.guesses.len() {
+        if step {
+            println!("{}", t!("replay_next"));
+            if read_line().is_none() {
+                return;
+            }
+        }
+        let so_far = Game::replay(config, game.secret(), &log.guesses[..turn]);
+        let (guess, feedback) = so_far.history()[turn - 1];
+        let feasible = so_far.feasible();
+        println!(
+            "{:>2}. {:>6} {}  [{}]  {}-{}",
+            turn,
+            guess,
+            verdict(feedback),
+            tui::range_bar(config.min, config.max, *feasible.start(), *feasible.end(), 40),
+            feasible.start(),
+            feasible.end()
+        );
+    }
+
+    match game.state() {
+        GameState::Won => println!("{}", t!("replay_won", secret = game.secret())),
+        GameState::OutOfAttempts => println!("{}", t!("replay_lost", secret = game.secret())),
+        GameState::AwaitingGuess => println!("{}", t!("replay_unfinished")),
+    }
+}
+
+/// A short description of a counted guess, for resumed games and replays.
+fn verdict(feedback: Feedback) -> String {
+    match feedback {
+        Feedback::TooSmall => t!("verdict_too_small"),
+        Feedback::TooBig => t!("verdict_too_big"),
+        _ => t!("verdict_correct"),
+    }
+}
+
+/// The classic one-line-per-event game. Returns `false` if input ran out first.
+fn play_lines(config: &Config, game: &mut Game, hints: &mut Hints, checkpoint: &dyn Fn(&Game, &Hints)) -> bool {
+    println!("{}", t!("guess_the_number"));
+    match config.max_attempts {
+        Some(max) => println!("{}", tn!("difficulty_attempts", max, difficulty = config.difficulty)),
+        None => println!("{}", t!("difficulty_unlimited", difficulty = config.difficulty)),
+    }
+
+    if config.hints.budget > 0 {
+        println!(
+            "{}",
+            t!(
+                "hint_intro",
+                points = config.hints.budget,
+                parity = config.hints.costs.parity,
+                div = config.hints.costs.divisible_by,
+                digits = config.hints.costs.digit_sum,
+            )
+        );
+    }
+
+    while !game.is_over() {
+        println!("{}", config.prompt());
+
+        let Some(guess) = read_line() else {
+            return false;
+        };
+
+        if let Some(request) = guess.trim().strip_prefix("hint") {
+            match HintKind::parse(request) {
+                Some(kind) => match hints.buy(kind, game) {
+                    Ok(revealed) => {
+                        println!("{}", tn!("hint_bought", hints.points(), revealed = revealed));
+                        checkpoint(game, hints);
+                    }
+                    Err(e) => println!("❌ {}!", e),
+                },
+                None => println!("{}", t!("hint_usage")),
+            }
+            continue;
+        }
+
         let guess: u32 = match guess.trim().parse() {
             Ok(num) => num,
             Err(_) => {
-                println!("❌ Please enter a valid number!");
+                println!("{}", t!("invalid_number"));
+                continue;
+            }
+        };
+
+        println!("{}", t!("you_guessed", guess = guess));
+
+        match game.guess(guess) {
+            Feedback::TooSmall => println!("{}", t!("too_small")),
+            Feedback::TooBig => println!("{}", t!("too_big")),
+            Feedback::Correct => println!("{}", t!("you_win", secret = game.secret())),
+            Feedback::OutOfRange => {
+                println!("{}", t!("out_of_range", guess = guess, min = config.min, max = config.max));
                 continue;
             }
+            Feedback::GameOver => break,
+        }
+
+        if game.is_over() {
+            break;
+        }
+
+        let free = hints.after_guess(guess, game);
+        if !free.is_empty() {
+            println!("{}", free);
+        }
+        checkpoint(game, hints);
+
+        if let Some(left) = game.attempts_left() {
+            println!("{}", tn!("attempts_left", left));
+        }
+    }
+    true
+}
+
+fn play_reverse(config: &Config) {
+    let range = config.range();
+    println!("{}", t!("reverse_intro", min = config.min, max = config.max));
+    println!("{}", tn!("reverse_worst_case", reverse::worst_case_guesses(&range)));
+    println!("{}", t!("reverse_how_to_answer"));
+
+    let mut guesser = Guesser::new(range);
+
+    loop {
+        let prompt = t!("reverse_prompt", guess = guesser.current_guess());
+        let answer = read_valid(&prompt, |input| Answer::parse(input).ok_or_else(|| t!("reverse_bad_answer")));
+        let Some(answer) = answer else {
+            println!("{}", t!("bye"));
+            return;
+        };
+
+        match guesser.answer(answer) {
+            Ok(Step::Continue) => {}
+            Ok(Step::Found(number)) => {
+                println!("{}", tn!("reverse_found", guesser.guesses(), number = number));
+                return;
+            }
+            Err(contradiction) => {
+                println!("{}", t!("reverse_contradiction", reason = contradiction));
+                println!("{}", t!("reverse_try_again"));
+            }
+        }
+    }
+}
+
+fn play_bulls(rules: Rules) {
+    println!("{}", t!("bulls_intro"));
+    let layout = if rules.duplicates() { "bulls_rules_repeats" } else { "bulls_rules_distinct" };
+    println!("{}", tn!(layout, rules.length(), alphabet = rules.alphabet()));
+    println!("{}", t!("bulls_explained"));
+
+    let mut game = CodeGame::new(rules, &mut rand::thread_rng());
+
+    while game.outcome() == Outcome::Playing {
+        let Some(score) = read_valid(&t!("bulls_prompt"), |input| game.guess(input)) else {
+            println!("{}", t!("bulls_bye", secret = game.secret()));
+            return;
+        };
+        println!("{}", score);
+
+        if let (Some(left), Outcome::Playing) = (game.attempts_left(), game.outcome()) {
+            println!("{}", tn!("attempts_left", left));
+        }
+    }
+
+    match game.outcome() {
+        Outcome::Won => println!("{}", tn!("bulls_won", game.attempts())),
+        _ => println!("{}", t!("bulls_lost", secret = game.secret())),
+    }
+}
+
+fn solve_bulls(rules: Rules, code: &str) {
+    let secret = rules.parse(code).expect("validated by the command line");
+    let mut solver = match Solver::new(rules.clone()) {
+        Ok(solver) => solver,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+
+    println!("{}", t!("solver_intro", code = code, codes = solver.remaining()));
+
+    let mut step = 1;
+    while let Some(guess) = solver.next_guess() {
+        let score = rules.score(&secret, &guess);
+        solver.record(&guess, score);
+        println!(
+            "  {}",
+            tn!(
+                "solver_step",
+                solver.remaining(),
+                step = format!("{:>2}", step),
+                guess = rules.show(&guess),
+                score = score,
+            )
+        );
+        if score.bulls == rules.length() {
+            println!("{}", tn!("solver_solved", step));
+            return;
+        }
+        step += 1;
+    }
+}
+
+fn run_bench(config: &Config, games: usize, seed: u64, only: Option<&str>) {
+    const HISTOGRAM_BUCKETS: usize = 10;
+    const BAR_WIDTH: usize = 40;
+
+    println!("{}", tn!("bench_intro", games, min = config.min, max = config.max, seed = seed));
+    println!(
+        "\n{:<8} {:>8} {:>8} {:>6}",
+        t!("bench_strategy"),
+        t!("bench_mean"),
+        t!("bench_median"),
+        t!("bench_max")
+    );
+
+    let results: Vec<_> = strategy::all(seed)
+        .into_iter()
+        .filter(|s| only.is_none_or(|name| s.name() == name))
+        .map(|mut s| bench::run(s.as_mut(), config.range(), games, seed))
+        .collect();
+
+    for result in &results {
+        println!(
+            "{:<8} {:>8.2} {:>8.1} {:>6}",
+            result.strategy,
+            result.mean(),
+            result.median(),
+            result.max()
+        );
+    }
+
+    for result in &results {
+        println!("\n{}", t!("bench_histogram", strategy = result.strategy));
+        let histogram = result.histogram(HISTOGRAM_BUCKETS);
+        let tallest = histogram.iter().map(|(_, count)| *count).max().unwrap_or(1).max(1);
+        for (bucket, count) in histogram {
+            let label = if bucket.start() == bucket.end() {
+                bucket.start().to_string()
+            } else {
+                format!("{}-{}", bucket.start(), bucket.end())
+            };
+            let bar = "#".repeat(count * BAR_WIDTH / tallest);
+            println!("  {:>9} | {:<width$} {}", label, bar, count, width = BAR_WIDTH);
+        }
+    }
+}
+
+fn serve(config: Config, host: &str, port: u16, mode: Mode) {
+    let listener = match TcpListener::bind((host, port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("{}", t!("listen_failed", host = host, port = port, error = e));
+            process::exit(1);
+        }
+    };
+
+    println!(
+        "{}",
+        t!("hosting", mode = mode, min = config.min, max = config.max, host = host, port = port)
+    );
+    println!("{}", t!("how_to_join", port = port));
+
+    let rng = Box::new(StdRng::from_entropy());
+    if let Err(e) = multiplayer::serve(listener, Room::new(config, mode, rng)) {
+        eprintln!("{}", t!("server_stopped", error = e));
+        process::exit(1);
+    }
+}
+
+fn join(host: &str, port: u16, player: &str) {
+    let client = match Client::connect((host, port), player) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}", t!("connect_failed", host = host, port = port, error = e));
+            process::exit(1);
+        }
+    };
+    let (mut writer, reader) = client.into_parts();
+
+    let me = player.to_string();
+    thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else { break };
+            match ServerMessage::parse(&line) {
+                Some(message) => print_server_message(&message, &me),
+                None => println!("{}", line),
+            }
+        }
+        println!("{}", t!("server_closed"));
+        process::exit(0);
+    });
+
+    println!("{}", t!("join_help"));
+    while let Some(input) = read_line() {
+        let input = input.trim();
+        let message = match input.to_lowercase().as_str() {
+            "" => continue,
+            "hint" => ClientMessage::Hint,
+            "quit" => break,
+            _ => match input.parse() {
+                Ok(n) => ClientMessage::Guess(n),
+                Err(_) => {
+                    println!("{}", t!("invalid_number"));
+                    continue;
+                }
+            },
         };
-        
-        println!("You guessed: {}", guess);
-        
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("📉 Too small!"),
-            Ordering::Greater => println!("📈 Too big!"),
-            Ordering::Equal => {
-                println!("🎉 You win! The number was {}!", secret_number);
-                break;
+        if writeln!(writer, "{}", message).is_err() {
+            break;
+        }
+    }
+    let _ = writeln!(writer, "{}", ClientMessage::Quit);
+}
+
+fn print_server_message(message: &ServerMessage, me: &str) {
+    match message {
+        ServerMessage::Welcome { min, max, mode } => {
+            println!("{}", t!("welcome", min = min, max = max, mode = mode))
+        }
+        ServerMessage::Joined(name) => println!("{}", t!("player_joined", name = name)),
+        ServerMessage::Left(name) => println!("{}", t!("player_left", name = name)),
+        ServerMessage::Feedback { feedback, guess } => match feedback {
+            Feedback::TooSmall => println!("{}", t!("guess_too_small", guess = guess)),
+            Feedback::TooBig => println!("{}", t!("guess_too_big", guess = guess)),
+            Feedback::OutOfRange => println!("{}", t!("guess_outside", guess = guess)),
+            _ => {}
+        },
+        ServerMessage::Guessed { name, guess, feedback } => {
+            let key = if *feedback == Feedback::TooSmall { "player_guessed_too_small" } else { "player_guessed_too_big" };
+            if name != me {
+                println!("{}", t!(key, name = name, guess = guess));
             }
         }
+        ServerMessage::Hint { low, high } => println!("{}", t!("between", low = low, high = high)),
+        ServerMessage::Turn(name) if name == me => println!("{}", t!("your_turn")),
+        ServerMessage::Turn(name) => println!("{}", t!("player_turn", name = name)),
+        ServerMessage::Win { name, secret } if name == me => println!("{}", t!("you_win", secret = secret)),
+        ServerMessage::Win { name, secret } => println!("{}", t!("player_wins", name = name, secret = secret)),
+        ServerMessage::Round { min, max } => println!("{}", t!("new_round", min = min, max = max)),
+        ServerMessage::Error(e) => println!("❌ {}", e),
+    }
+}
+
+fn show_scores() {
+    let store = match ScoreStore::open_default() {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+    let records = match store.load() {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("{}", t!("scores_unreadable", path = store.path().display(), error = e));
+            process::exit(1);
+        }
+    };
+
+    if records.is_empty() {
+        println!("{}", t!("no_scores"));
+        return;
+    }
+
+    println!("{}", t!("leaderboards"));
+    for difficulty in Difficulty::ALL {
+        let board = scores::leaderboard(&records, difficulty);
+        if board.is_empty() {
+            continue;
+        }
+        println!("\n{}:", difficulty);
+        for (rank, record) in board.iter().enumerate() {
+            println!(
+                "  {}",
+                tn!(
+                    "leaderboard_row",
+                    record.attempts,
+                    rank = format!("{:>2}", rank + 1),
+                    player = format!("{:<12}", record.player),
+                    attempts = format!("{:>3}", record.attempts),
+                    time = format!("{:>6.1}", record.elapsed_ms as f64 / 1000.0),
+                    date = record.date(),
+                )
+            );
+        }
+    }
+
+    println!("\n{}", t!("players"));
+    for stats in scores::player_stats(&records) {
+        let average = match stats.average_attempts {
+            Some(average) => format!("{:.1}", average),
+            None => String::from("-"),
+        };
+        println!(
+            "  {}",
+            tn!(
+                "player_row",
+                stats.games,
+                player = format!("{:<12}", stats.player),
+                games = format!("{:>3}", stats.games),
+                wins = format!("{:>5.1}", stats.win_rate() * 100.0),
+                average = format!("{:>5}", average),
+                streak = stats.best_streak,
+            )
+        );
     }
 }
@@ -0,0 +1,670 @@
+// multiplayer.rs
+// Several players racing (or taking turns) to guess the same secret over TCP.
+//
+// The protocol is one message per line:
+//
+//   client → server            server → client
+//   JOIN <name>                WELCOME <min> <max> <mode>
+//   GUESS <n>                  JOINED <name> / LEFT <name>
+//   HINT                       TOO_SMALL <n> / TOO_BIG <n> / OUT_OF_RANGE <n>
+//   QUIT                       GUESSED <name> <n> <TOO_SMALL|TOO_BIG>   (turn mode)
+//                              HINT <low> <high>
+//                              TURN <name>                              (turn mode)
+//                              WIN <name> <secret>
+//                              ROUND <min> <max>
+//                              ERR <message>
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::config::Config;
+use crate::game::{Feedback, Game, Rng};
+use crate::t;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Everyone guesses whenever they like; first correct guess wins.
+    Race,
+    /// Players guess one at a time in the order they joined.
+    Turns,
+}
+
+impl Mode {
+    pub fn parse(name: &str) -> Option<Mode> {
+        match name.to_lowercase().as_str() {
+            "race" => Some(Mode::Race),
+            "turn" | "turns" => Some(Mode::Turns),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Race => write!(f, "race"),
+            Mode::Turns => write!(f, "turns"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    Join(String),
+    Guess(u32),
+    Hint,
+    Quit,
+}
+
+impl ClientMessage {
+    pub fn parse(line: &str) -> Result<ClientMessage, String> {
+        let mut parts = line.split_whitespace();
+        let command = parts.next().unwrap_or("").to_uppercase();
+        let argument = parts.next();
+
+        match (command.as_str(), argument) {
+            // Names go out in single-word fields like `JOINED <name>`
+            ("JOIN", Some(_)) if parts.next().is_some() => Err(String::from("names must be a single word")),
+            ("JOIN", Some(name)) => Ok(ClientMessage::Join(name.to_string())),
+            ("GUESS", Some(n)) => n
+                .parse()
+                .map(ClientMessage::Guess)
+                .map_err(|_| format!("not a number: {}", n)),
+            ("HINT", None) => Ok(ClientMessage::Hint),
+            ("QUIT", None) => Ok(ClientMessage::Quit),
+            _ => Err(format!("bad command: {}", line.trim())),
+        }
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::Join(name) => write!(f, "JOIN {}", name),
+            ClientMessage::Guess(n) => write!(f, "GUESS {}", n),
+            ClientMessage::Hint => write!(f, "HINT"),
+            ClientMessage::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    Welcome { min: u32, max: u32, mode: Mode },
+    Joined(String),
+    Left(String),
+    /// The answer to your own guess.
+    Feedback { feedback: Feedback, guess: u32 },
+    /// Someone else's guess, shared with everyone in turn mode.
+    Guessed { name: String, guess: u32, feedback: Feedback },
+    Hint { low: u32, high: u32 },
+    Turn(String),
+    Win { name: String, secret: u32 },
+    Round { min: u32, max: u32 },
+    Error(String),
+}
+
+fn feedback_word(feedback: Feedback) -> &'static str {
+    match feedback {
+        Feedback::TooSmall => "TOO_SMALL",
+        Feedback::TooBig => "TOO_BIG",
+        Feedback::Correct => "CORRECT",
+        Feedback::OutOfRange => "OUT_OF_RANGE",
+        Feedback::GameOver => "GAME_OVER",
+    }
+}
+
+fn parse_feedback(word: &str) -> Option<Feedback> {
+    match word {
+        "TOO_SMALL" => Some(Feedback::TooSmall),
+        "TOO_BIG" => Some(Feedback::TooBig),
+        "CORRECT" => Some(Feedback::Correct),
+        "OUT_OF_RANGE" => Some(Feedback::OutOfRange),
+        "GAME_OVER" => Some(Feedback::GameOver),
+        _ => None,
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Welcome { min, max, mode } => write!(f, "WELCOME {} {} {}", min, max, mode),
+            ServerMessage::Joined(name) => write!(f, "JOINED {}", name),
+            ServerMessage::Left(name) => write!(f, "LEFT {}", name),
+            ServerMessage::Feedback { feedback, guess } => {
+                write!(f, "{} {}", feedback_word(*feedback), guess)
+            }
+            ServerMessage::Guessed { name, guess, feedback } => {
+                write!(f, "GUESSED {} {} {}", name, guess, feedback_word(*feedback))
+            }
+            ServerMessage::Hint { low, high } => write!(f, "HINT {} {}", low, high),
+            ServerMessage::Turn(name) => write!(f, "TURN {}", name),
+            ServerMessage::Win { name, secret } => write!(f, "WIN {} {}", name, secret),
+            ServerMessage::Round { min, max } => write!(f, "ROUND {} {}", min, max),
+            ServerMessage::Error(message) => write!(f, "ERR {}", message),
+        }
+    }
+}
+
+impl ServerMessage {
+    pub fn parse(line: &str) -> Option<ServerMessage> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let number = |i: usize| parts.get(i).and_then(|p| p.parse::<u32>().ok());
+        let word = |i: usize| parts.get(i).map(|p| p.to_string());
+
+        match parts.first().copied()? {
+            "WELCOME" => Some(ServerMessage::Welcome {
+                min: number(1)?,
+                max: number(2)?,
+                mode: Mode::parse(parts.get(3)?)?,
+            }),
+            "JOINED" => Some(ServerMessage::Joined(word(1)?)),
+            "LEFT" => Some(ServerMessage::Left(word(1)?)),
+            "GUESSED" => Some(ServerMessage::Guessed {
+                name: word(1)?,
+                guess: number(2)?,
+                feedback: parse_feedback(parts.get(3)?)?,
+            }),
+            "HINT" => Some(ServerMessage::Hint { low: number(1)?, high: number(2)? }),
+            "TURN" => Some(ServerMessage::Turn(word(1)?)),
+            "WIN" => Some(ServerMessage::Win { name: word(1)?, secret: number(2)? }),
+            "ROUND" => Some(ServerMessage::Round { min: number(1)?, max: number(2)? }),
+            "ERR" => Some(ServerMessage::Error(line.trim()[3..].trim().to_string())),
+            other => Some(ServerMessage::Feedback { feedback: parse_feedback(other)?, guess: number(1)? }),
+        }
+    }
+}
+
+pub type PlayerId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    One(PlayerId),
+    All,
+}
+
+/// A message and who should receive it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outgoing {
+    pub to: Target,
+    pub message: ServerMessage,
+}
+
+impl Outgoing {
+    fn one(id: PlayerId, message: ServerMessage) -> Outgoing {
+        Outgoing { to: Target::One(id), message }
+    }
+
+    fn all(message: ServerMessage) -> Outgoing {
+        Outgoing { to: Target::All, message }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Player {
+    id: PlayerId,
+    name: String,
+    /// What this player's own guesses have narrowed the secret down to.
+    low: u32,
+    high: u32,
+}
+
+/// The shared game, independent of sockets. Connections that haven't sent
+/// `JOIN` yet are not players.
+pub struct Room {
+    config: Config,
+    mode: Mode,
+    game: Game,
+    players: Vec<Player>,
+    /// Index into `players` of whoever may guess next (turn mode only).
+    turn: usize,
+    rng: Box<dyn Rng + Send>,
+}
+
+impl Room {
+    pub fn new(config: Config, mode: Mode, mut rng: Box<dyn Rng + Send>) -> Room {
+        let game = Game::new(config.range(), None, rng.as_mut());
+        Room { config, mode, game, players: Vec::new(), turn: 0, rng }
+    }
+
+    pub fn player_names(&self) -> Vec<&str> {
+        self.players.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    pub fn handle(&mut self, id: PlayerId, message: ClientMessage) -> Vec<Outgoing> {
+        let joined = self.players.iter().position(|p| p.id == id);
+
+        match (message, joined) {
+            (ClientMessage::Join(name), None) => self.join(id, name),
+            (ClientMessage::Join(_), Some(_)) => vec![error(id, "already joined")],
+            (ClientMessage::Quit, Some(_)) => self.leave(id),
+            (ClientMessage::Quit, None) => Vec::new(),
+            (_, None) => vec![error(id, "send JOIN <name> first")],
+            (ClientMessage::Hint, Some(index)) => {
+                let player = &self.players[index];
+                vec![Outgoing::one(id, ServerMessage::Hint { low: player.low, high: player.high })]
+            }
+            (ClientMessage::Guess(guess), Some(index)) => self.guess(index, guess),
+        }
+    }
+
+    fn join(&mut self, id: PlayerId, name: String) -> Vec<Outgoing> {
+        if self.players.iter().any(|p| p.name == name) {
+            return vec![error(id, "name already taken")];
+        }
+
+        self.players.push(Player { id, name: name.clone(), low: self.config.min, high: self.config.max });
+
+        let mut out = vec![
+            Outgoing::one(
+                id,
+                ServerMessage::Welcome { min: self.config.min, max: self.config.max, mode: self.mode },
+            ),
+            Outgoing::all(ServerMessage::Joined(name)),
+        ];
+        if self.mode == Mode::Turns {
+            out.push(self.whose_turn());
+        }
+        out
+    }
+
+    /// Removes a player, e.g. when their connection drops.
+    pub fn leave(&mut self, id: PlayerId) -> Vec<Outgoing> {
+        let Some(index) = self.players.iter().position(|p| p.id == id) else {
+            return Vec::new();
+        };
+        let player = self.players.remove(index);
+
+        let mut out = vec![Outgoing::all(ServerMessage::Left(player.name))];
+        if self.mode == Mode::Turns && !self.players.is_empty() {
+            let had_turn = index == self.turn;
+            if index < self.turn {
+                self.turn -= 1;
+            }
+            self.turn %= self.players.len();
+            if had_turn {
+                out.push(self.whose_turn());
+            }
+        }
+        out
+    }
+
+    fn guess(&mut self, index: usize, guess: u32) -> Vec<Outgoing> {
+        let id = self.players[index].id;
+        if self.mode == Mode::Turns && index != self.turn {
+            return vec![error(id, "not your turn")];
+        }
+
+        let feedback = self.game.guess(guess);
+        let player = &mut self.players[index];
+        match feedback {
+            Feedback::OutOfRange => {
+                return vec![Outgoing::one(id, ServerMessage::Feedback { feedback, guess })];
+            }
+            Feedback::Correct => {
+                let win = ServerMessage::Win { name: player.name.clone(), secret: guess };
+                return self.new_round(win);
+            }
+            Feedback::TooSmall => player.low = player.low.max(guess + 1),
+            Feedback::TooBig => player.high = player.high.min(guess - 1),
+            Feedback::GameOver => unreachable!("rounds never run out of attempts"),
+        }
+
+        let mut out = vec![Outgoing::one(id, ServerMessage::Feedback { feedback, guess })];
+        if self.mode == Mode::Turns {
+            let name = self.players[index].name.clone();
+            out.push(Outgoing::all(ServerMessage::Guessed { name, guess, feedback }));
+            // In turn mode every guess is public, so everyone learns from it.
+            for other in &mut self.players {
+                match feedback {
+                    Feedback::TooSmall => other.low = other.low.max(guess + 1),
+                    _ => other.high = other.high.min(guess - 1),
+                }
+            }
+            self.turn = (self.turn + 1) % self.players.len();
+            out.push(self.whose_turn());
+        }
+        out
+    }
+
+    fn new_round(&mut self, win: ServerMessage) -> Vec<Outgoing> {
+        self.game = Game::new(self.config.range(), None, self.rng.as_mut());
+        for player in &mut self.players {
+            player.low = self.config.min;
+            player.high = self.config.max;
+        }
+
+        let mut out = vec![
+            Outgoing::all(win),
+            Outgoing::all(ServerMessage::Round { min: self.config.min, max: self.config.max }),
+        ];
+        if self.mode == Mode::Turns {
+            self.turn = (self.turn + 1) % self.players.len();
+            out.push(self.whose_turn());
+        }
+        out
+    }
+
+    fn whose_turn(&self) -> Outgoing {
+        Outgoing::all(ServerMessage::Turn(self.players[self.turn].name.clone()))
+    }
+}
+
+fn error(id: PlayerId, message: &str) -> Outgoing {
+    Outgoing::one(id, ServerMessage::Error(message.to_string()))
+}
+
+/// How many lines may wait for a client that is slow to read them. A client
+/// that falls further behind than this is disconnected.
+const OUTBOX_SIZE: usize = 64;
+
+/// A connected socket. Its lines go through `outbox` to a writer thread of
+/// its own, so a client that stops reading only ever holds up itself.
+struct Connection {
+    outbox: SyncSender<String>,
+    /// Kept to hang up on the client from outside its threads.
+    stream: TcpStream,
+}
+
+struct Shared {
+    room: Room,
+    clients: HashMap<PlayerId, Connection>,
+}
+
+impl Shared {
+    /// Starts the writer thread for a new connection.
+    fn connect(&mut self, id: PlayerId, stream: &TcpStream) -> io::Result<()> {
+        let (outbox, lines) = mpsc::sync_channel(OUTBOX_SIZE);
+        let writer = stream.try_clone()?;
+        thread::spawn(move || write_lines(writer, lines));
+        self.clients.insert(id, Connection { outbox, stream: stream.try_clone()? });
+        Ok(())
+    }
+
+    /// Hangs up on a client. Its threads notice and stop.
+    fn disconnect(&mut self, id: PlayerId) {
+        if let Some(connection) = self.clients.remove(&id) {
+            let _ = connection.stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Queues each message for its recipients without waiting on any socket,
+    /// so it is fine to call with the lock held. A client whose outbox is
+    /// full or whose writer has stopped is dropped from the room, and the
+    /// others are told it left.
+    fn deliver(&mut self, outgoing: Vec<Outgoing>) {
+        let mut queue = VecDeque::from(outgoing);
+        while let Some(Outgoing { to, message }) = queue.pop_front() {
+            let line = format!("{}\n", message);
+            let recipients = match to {
+                Target::One(id) => vec![id],
+                Target::All => self.room.players.iter().map(|p| p.id).collect(),
+            };
+            for id in recipients {
+                let Some(connection) = self.clients.get(&id) else { continue };
+                if connection.outbox.try_send(line.clone()).is_err() {
+                    self.disconnect(id);
+                    queue.extend(self.room.leave(id));
+                }
+            }
+        }
+    }
+}
+
+/// Sends a client its lines until the outbox closes or a write fails.
+fn write_lines(mut stream: TcpStream, lines: Receiver<String>) {
+    for line in lines {
+        if stream.write_all(line.as_bytes()).is_err() {
+            // The connection's reader sees the socket close and takes the
+            // player out of the room
+            let _ = stream.shutdown(Shutdown::Both);
+            break;
+        }
+    }
+}
+
+/// Accepts players on `listener` forever, one thread per connection. A
+/// connection that fails while being set up is reported and skipped, so it
+/// does not stop the game for everyone else.
+pub fn serve(listener: TcpListener, room: Room) -> io::Result<()> {
+    let shared = Arc::new(Mutex::new(Shared { room, clients: HashMap::new() }));
+
+    for (id, stream) in listener.incoming().enumerate() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("{}", t!("accept_failed", error = e));
+                continue;
+            }
+        };
+        if let Err(e) = shared.lock().unwrap().connect(id, &stream) {
+            eprintln!("{}", t!("connection_failed", error = e));
+            continue;
+        }
+
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let reader = BufReader::new(stream);
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let mut shared = shared.lock().unwrap();
+                let outgoing = match ClientMessage::parse(&line) {
+                    Ok(ClientMessage::Quit) => break,
+                    Ok(message) => shared.room.handle(id, message),
+                    Err(e) => vec![error(id, &e)],
+                };
+                shared.deliver(outgoing);
+            }
+
+            let mut shared = shared.lock().unwrap();
+            let outgoing = shared.room.leave(id);
+            shared.disconnect(id);
+            shared.deliver(outgoing);
+        });
+    }
+    Ok(())
+}
+
+/// A connected player: send messages with `send`, read the server's lines with `messages`.
+pub struct Client {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Client {
+    pub fn connect(addr: impl ToSocketAddrs, name: &str) -> io::Result<Client> {
+        let stream = TcpStream::connect(addr)?;
+        let mut client = Client { writer: stream.try_clone()?, reader: BufReader::new(stream) };
+        client.send(&ClientMessage::Join(name.to_string()))?;
+        Ok(client)
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        writeln!(self.writer, "{}", message)
+    }
+
+    /// Blocks until the next message; `None` once the server hangs up.
+    pub fn receive(&mut self) -> io::Result<Option<ServerMessage>> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if let Some(message) = ServerMessage::parse(&line) {
+                return Ok(Some(message));
+            }
+        }
+    }
+
+    /// Splits the client so one thread can read while another writes.
+    pub fn into_parts(self) -> (TcpStream, BufReader<TcpStream>) {
+        (self.writer, self.reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Fixed;
+
+    fn room(mode: Mode) -> Room {
+        Room::new(Config::custom(1, 100, None).unwrap(), mode, Box::new(Fixed(42)))
+    }
+
+    fn messages_to(out: &[Outgoing], id: PlayerId) -> Vec<ServerMessage> {
+        out.iter()
+            .filter(|o| o.to == Target::All || o.to == Target::One(id))
+            .map(|o| o.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn protocol_round_trips() {
+        let messages = [
+            ServerMessage::Welcome { min: 1, max: 100, mode: Mode::Turns },
+            ServerMessage::Feedback { feedback: Feedback::TooBig, guess: 7 },
+            ServerMessage::Guessed { name: String::from("ann"), guess: 3, feedback: Feedback::TooSmall },
+            ServerMessage::Hint { low: 4, high: 6 },
+            ServerMessage::Win { name: String::from("bob"), secret: 5 },
+            ServerMessage::Error(String::from("not your turn")),
+        ];
+        for message in messages {
+            assert_eq!(ServerMessage::parse(&message.to_string()), Some(message));
+        }
+
+        assert_eq!(ClientMessage::parse("guess 12"), Ok(ClientMessage::Guess(12)));
+        assert_eq!(ClientMessage::parse("JOIN ann"), Ok(ClientMessage::Join(String::from("ann"))));
+        assert_eq!(ClientMessage::parse("JOIN ann lee"), Err(String::from("names must be a single word")));
+        assert!(ClientMessage::parse("GUESS twelve").is_err());
+        assert!(ClientMessage::parse("DANCE").is_err());
+    }
+
+    #[test]
+    fn must_join_before_guessing() {
+        let mut room = room(Mode::Race);
+        let out = room.handle(0, ClientMessage::Guess(5));
+        assert!(matches!(out[0].message, ServerMessage::Error(_)));
+
+        room.handle(0, ClientMessage::Join(String::from("ann")));
+        let out = room.handle(1, ClientMessage::Join(String::from("ann")));
+        assert_eq!(out, vec![error(1, "name already taken")]);
+    }
+
+    #[test]
+    fn race_mode_hints_and_win() {
+        let mut room = room(Mode::Race);
+        room.handle(0, ClientMessage::Join(String::from("ann")));
+        room.handle(1, ClientMessage::Join(String::from("bob")));
+
+        room.handle(0, ClientMessage::Guess(30));
+        room.handle(0, ClientMessage::Guess(60));
+        let hint = room.handle(0, ClientMessage::Hint);
+        assert_eq!(hint[0].message, ServerMessage::Hint { low: 31, high: 59 });
+
+        // Bob's hint is unaffected by Ann's guesses in a race.
+        let hint = room.handle(1, ClientMessage::Hint);
+        assert_eq!(hint[0].message, ServerMessage::Hint { low: 1, high: 100 });
+
+        let out = room.handle(1, ClientMessage::Guess(42));
+        assert_eq!(out[0], Outgoing::all(ServerMessage::Win { name: String::from("bob"), secret: 42 }));
+        assert_eq!(out[1], Outgoing::all(ServerMessage::Round { min: 1, max: 100 }));
+    }
+
+    #[test]
+    fn turn_mode_enforces_order() {
+        let mut room = room(Mode::Turns);
+        room.handle(0, ClientMessage::Join(String::from("ann")));
+        let out = room.handle(1, ClientMessage::Join(String::from("bob")));
+        assert!(messages_to(&out, 1).contains(&ServerMessage::Turn(String::from("ann"))));
+
+        let out = room.handle(1, ClientMessage::Guess(10));
+        assert_eq!(out, vec![error(1, "not your turn")]);
+
+        let out = room.handle(0, ClientMessage::Guess(10));
+        assert!(messages_to(&out, 1).contains(&ServerMessage::Turn(String::from("bob"))));
+
+        // Public guesses narrow everyone's hint.
+        let hint = room.handle(1, ClientMessage::Hint);
+        assert_eq!(hint[0].message, ServerMessage::Hint { low: 11, high: 100 });
+
+        // Bob leaving on his turn hands it back to Ann.
+        let out = room.leave(1);
+        assert!(out.contains(&Outgoing::all(ServerMessage::Turn(String::from("ann")))));
+        assert_eq!(room.player_names(), vec!["ann"]);
+    }
+
+    #[test]
+    fn clients_that_cannot_keep_up_are_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _sockets: Vec<TcpStream> = (0..2).map(|_| TcpStream::connect(addr).unwrap()).collect();
+        let streams: Vec<TcpStream> = (0..2).map(|_| listener.accept().unwrap().0).collect();
+
+        // Outboxes of our own, without writer threads, to look into
+        let mut shared = Shared { room: room(Mode::Race), clients: HashMap::new() };
+        let (ann_outbox, ann_lines) = mpsc::sync_channel(OUTBOX_SIZE);
+        let (bob_outbox, bob_lines) = mpsc::sync_channel(1);
+        shared.clients.insert(0, Connection { outbox: ann_outbox, stream: streams[0].try_clone().unwrap() });
+        shared.clients.insert(1, Connection { outbox: bob_outbox, stream: streams[1].try_clone().unwrap() });
+        let joins = [(0, "ann"), (1, "bob")].map(|(id, name)| shared.room.handle(id, ClientMessage::Join(name.into())));
+        for outgoing in joins {
+            shared.deliver(outgoing);
+        }
+
+        // Bob never reads, so his one-line outbox overflowed
+        assert_eq!(shared.room.player_names(), vec!["ann"]);
+        assert!(!shared.clients.contains_key(&1));
+        assert_eq!(bob_lines.try_iter().count(), 1);
+        let ann: Vec<String> = ann_lines.try_iter().collect();
+        assert_eq!(ann.last().map(String::as_str), Some("LEFT bob\n"));
+
+        // A writer that stopped closes the outbox; the client is dropped the same way
+        drop(ann_lines);
+        shared.deliver(vec![Outgoing::all(ServerMessage::Round { min: 1, max: 100 })]);
+        assert!(shared.room.player_names().is_empty());
+        assert!(shared.clients.is_empty());
+    }
+
+    #[test]
+    fn two_clients_play_over_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, room(Mode::Race)));
+
+        let mut ann = Client::connect(addr, "ann").unwrap();
+        assert_eq!(
+            ann.receive().unwrap(),
+            Some(ServerMessage::Welcome { min: 1, max: 100, mode: Mode::Race })
+        );
+        assert_eq!(ann.receive().unwrap(), Some(ServerMessage::Joined(String::from("ann"))));
+
+        let mut bob = Client::connect(addr, "bob").unwrap();
+        bob.receive().unwrap();
+        assert_eq!(ann.receive().unwrap(), Some(ServerMessage::Joined(String::from("bob"))));
+        bob.receive().unwrap();
+
+        ann.send(&ClientMessage::Guess(50)).unwrap();
+        assert_eq!(
+            ann.receive().unwrap(),
+            Some(ServerMessage::Feedback { feedback: Feedback::TooBig, guess: 50 })
+        );
+
+        bob.send(&ClientMessage::Guess(42)).unwrap();
+        let win = Some(ServerMessage::Win { name: String::from("bob"), secret: 42 });
+        assert_eq!(ann.receive().unwrap(), win);
+        assert_eq!(bob.receive().unwrap(), win);
+
+        bob.send(&ClientMessage::Quit).unwrap();
+        assert_eq!(ann.receive().unwrap(), Some(ServerMessage::Round { min: 1, max: 100 }));
+        assert_eq!(ann.receive().unwrap(), Some(ServerMessage::Left(String::from("bob"))));
+    }
+}
@@ -0,0 +1,187 @@
+// reverse.rs
+// Reverse mode: the player picks a number and the computer bisects its way to it.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::t;
+
+/// The player's reply to one of the computer's guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// The secret is higher than the guess.
+    Higher,
+    /// The secret is lower than the guess.
+    Lower,
+    Correct,
+}
+
+impl Answer {
+    /// Accepts "higher"/"h", "lower"/"l" and "correct"/"c", in any case.
+    pub fn parse(input: &str) -> Option<Answer> {
+        match input.trim().to_lowercase().as_str() {
+            "h" | "higher" => Some(Answer::Higher),
+            "l" | "lower" => Some(Answer::Lower),
+            "c" | "correct" => Some(Answer::Correct),
+            _ => None,
+        }
+    }
+}
+
+/// The answers so far cannot all be true.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contradiction {
+    pub guess: u32,
+    pub answer: Answer,
+    /// What was still possible before the contradicting answer.
+    pub low: u32,
+    pub high: u32,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = match self.answer {
+            Answer::Higher => "contradiction_higher",
+            Answer::Lower => "contradiction_lower",
+            Answer::Correct => "contradiction_correct",
+        };
+        write!(f, "{}", t!(key, guess = self.guess, low = self.low, high = self.high))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Keep going; the next guess is ready.
+    Continue,
+    Found(u32),
+}
+
+/// Bisection over the numbers that are still consistent with every answer.
+#[derive(Debug, Clone)]
+pub struct Guesser {
+    low: u32,
+    high: u32,
+    guesses: u32,
+}
+
+impl Guesser {
+    pub fn new(range: RangeInclusive<u32>) -> Guesser {
+        Guesser { low: *range.start(), high: *range.end(), guesses: 0 }
+    }
+
+    /// The midpoint of the remaining interval.
+    pub fn current_guess(&self) -> u32 {
+        self.low + (self.high - self.low) / 2
+    }
+
+    pub fn remaining(&self) -> RangeInclusive<u32> {
+        self.low..=self.high
+    }
+
+    /// How many guesses have been answered so far.
+    pub fn guesses(&self) -> u32 {
+        self.guesses
+    }
+
+    /// Narrows the interval with the player's answer to `current_guess()`.
+    /// On a contradiction the interval is left unchanged.
+    pub fn answer(&mut self, answer: Answer) -> Result<Step, Contradiction> {
+        let guess = self.current_guess();
+        let contradiction = Contradiction { guess, answer, low: self.low, high: self.high };
+
+        match answer {
+            Answer::Correct => {
+                self.guesses += 1;
+                Ok(Step::Found(guess))
+            }
+            Answer::Higher if guess >= self.high => Err(contradiction),
+            Answer::Lower if guess <= self.low => Err(contradiction),
+            Answer::Higher => {
+                self.guesses += 1;
+                self.low = guess + 1;
+                Ok(Step::Continue)
+            }
+            Answer::Lower => {
+                self.guesses += 1;
+                self.high = guess - 1;
+                Ok(Step::Continue)
+            }
+        }
+    }
+}
+
+/// The most guesses bisection ever needs for `range`: ceil(log2(n + 1)).
+pub fn worst_case_guesses(range: &RangeInclusive<u32>) -> u32 {
+    let size = (*range.end() as u64).saturating_sub(*range.start() as u64) + 1;
+    64 - size.leading_zeros()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    fn solve(range: RangeInclusive<u32>, secret: u32) -> u32 {
+        let mut guesser = Guesser::new(range);
+        loop {
+            let answer = match secret.cmp(&guesser.current_guess()) {
+                Ordering::Greater => Answer::Higher,
+                Ordering::Less => Answer::Lower,
+                Ordering::Equal => Answer::Correct,
+            };
+            if let Step::Found(found) = guesser.answer(answer).unwrap() {
+                assert_eq!(found, secret);
+                return guesser.guesses();
+            }
+        }
+    }
+
+    #[test]
+    fn worst_case_matches_known_values() {
+        assert_eq!(worst_case_guesses(&(1..=1)), 1);
+        assert_eq!(worst_case_guesses(&(1..=3)), 2);
+        assert_eq!(worst_case_guesses(&(1..=100)), 7);
+        assert_eq!(worst_case_guesses(&(1..=1000)), 10);
+        assert_eq!(worst_case_guesses(&(0..=u32::MAX)), 33);
+    }
+
+    #[test]
+    fn finds_every_secret_within_the_worst_case() {
+        let range = 1..=100;
+        let bound = worst_case_guesses(&range);
+        for secret in range.clone() {
+            assert!(solve(range.clone(), secret) <= bound);
+        }
+    }
+
+    #[test]
+    fn contradictions_are_detected() {
+        let mut guesser = Guesser::new(1..=10);
+        assert_eq!(guesser.current_guess(), 5);
+        guesser.answer(Answer::Higher).unwrap(); // 6..=10, guess 8
+        guesser.answer(Answer::Lower).unwrap(); // 6..=7, guess 6
+        assert_eq!(guesser.current_guess(), 6);
+
+        let err = guesser.answer(Answer::Lower).unwrap_err();
+        assert_eq!((err.guess, err.low, err.high), (6, 6, 7));
+        assert_eq!(guesser.remaining(), 6..=7);
+    }
+
+    #[test]
+    fn edges_of_u32_do_not_overflow() {
+        let mut guesser = Guesser::new(0..=0);
+        assert!(guesser.answer(Answer::Lower).is_err());
+        assert!(guesser.answer(Answer::Higher).is_err());
+
+        assert!(solve(0..=u32::MAX, u32::MAX) <= 33);
+        assert!(solve(0..=u32::MAX, 0) <= 33);
+    }
+
+    #[test]
+    fn answers_parse_loosely() {
+        assert_eq!(Answer::parse(" H "), Some(Answer::Higher));
+        assert_eq!(Answer::parse("lower"), Some(Answer::Lower));
+        assert_eq!(Answer::parse("Correct"), Some(Answer::Correct));
+        assert_eq!(Answer::parse("maybe"), None);
+    }
+}
@@ -0,0 +1,184 @@
+// save.rs
+// Save files for interrupted games and logs of finished ones. Both use the
+// same format: the configuration, the secret and every guess so far.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::game::Game;
+use crate::hints::Hints;
+use crate::scores::data_dir;
+
+/// A game frozen in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub player: String,
+    pub config: Config,
+    /// Stored disguised, so a glance at the file does not spoil the game.
+    #[serde(with = "disguise")]
+    secret: u32,
+    /// Counted guesses, oldest first.
+    pub guesses: Vec<u32>,
+    pub hints: Hints,
+    pub elapsed_ms: u64,
+    /// Seconds since the Unix epoch when the file was written.
+    pub timestamp: u64,
+}
+
+impl SavedGame {
+    pub fn capture(player: &str, config: &Config, game: &Game, hints: &Hints, elapsed: Duration) -> SavedGame {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        SavedGame {
+            player: player.to_string(),
+            config: config.clone(),
+            secret: game.secret(),
+            guesses: game.history().iter().map(|&(guess, _)| guess).collect(),
+            hints: hints.clone(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            timestamp,
+        }
+    }
+
+    /// The game as it stood when it was saved.
+    pub fn game(&self) -> Game {
+        Game::replay(&self.config, self.secret, &self.guesses)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_millis(self.elapsed_ms)
+    }
+
+    pub fn load(path: &Path) -> io::Result<SavedGame> {
+        let contents = fs::read_to_string(path)?;
+        let saved: SavedGame =
+            serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let valid = saved.config.min <= saved.config.max && saved.config.range().contains(&saved.secret);
+        if !valid {
+            let message = format!("{} does not hold a valid game", path.display());
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+        Ok(saved)
+    }
+
+    /// Writes the file in one go: a crash half-way leaves the old file intact.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, path)
+    }
+
+    /// A file name for this game's log, e.g. `1760000000-ann.json`.
+    pub fn log_name(&self) -> String {
+        let player: String = self
+            .player
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}-{}.json", self.timestamp, player)
+    }
+}
+
+/// Where an interrupted game waits for `--resume`.
+pub fn save_path() -> io::Result<PathBuf> {
+    Ok(data_dir()?.join("save.json"))
+}
+
+/// Where finished games are logged for `replay`.
+pub fn log_dir() -> io::Result<PathBuf> {
+    Ok(data_dir()?.join("logs"))
+}
+
+/// Scrambles the secret with a random salt. This keeps honest players honest;
+/// it is not encryption.
+mod disguise {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    fn mask(salt: u32) -> u32 {
+        salt.wrapping_mul(0x9E37_79B9).rotate_left(13) ^ 0x5BD1_E995
+    }
+
+    pub fn hide(secret: u32, salt: u32) -> String {
+        format!("{:08x}{:08x}", salt, secret ^ mask(salt))
+    }
+
+    pub fn reveal(code: &str) -> Option<u32> {
+        if code.len() != 16 || !code.is_ascii() {
+            return None;
+        }
+        let salt = u32::from_str_radix(&code[..8], 16).ok()?;
+        let hidden = u32::from_str_radix(&code[8..], 16).ok()?;
+        Some(hidden ^ mask(salt))
+    }
+
+    pub fn serialize<S: Serializer>(secret: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hide(*secret, rand::random()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        reveal(&code).ok_or_else(|| serde::de::Error::custom("malformed secret"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Fixed, GameState};
+
+    #[test]
+    fn the_secret_is_disguised_and_comes_back() {
+        let code = disguise::hide(42, 7);
+        assert!(!code.contains("42"));
+        assert_eq!(disguise::reveal(&code), Some(42));
+        assert_ne!(disguise::hide(42, 8), code);
+        assert_eq!(disguise::reveal("not a secret"), None);
+    }
+
+    #[test]
+    fn a_saved_game_resumes_where_it_stopped() {
+        let config = Config::custom(1, 100, Some(5)).unwrap();
+        let mut game = Game::from_config(&config, &mut Fixed(42));
+        let mut hints = Hints::new(config.hints);
+        game.guess(50);
+        game.guess(0);
+        game.guess(25);
+        hints.buy(crate::hints::HintKind::Parity, &game).unwrap();
+
+        let saved = SavedGame::capture("ann", &config, &game, &hints, Duration::from_secs(3));
+        let path = std::env::temp_dir().join(format!("guessing_game_save_{}.json", std::process::id()));
+        saved.write(&path).unwrap();
+        let loaded = SavedGame::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, saved);
+        assert_eq!(loaded.guesses, vec![50, 25]);
+        let mut resumed = loaded.game();
+        assert_eq!(resumed.attempts_left(), Some(3));
+        assert_eq!(resumed.feasible(), 26..=49);
+        assert_eq!(loaded.hints.points(), hints.points());
+        resumed.guess(42);
+        assert_eq!(resumed.state(), GameState::Won);
+    }
+
+    #[test]
+    fn log_names_are_safe_file_names() {
+        let config = Config::normal();
+        let game = Game::from_config(&config, &mut Fixed(1));
+        let mut saved = SavedGame::capture("../ann b", &config, &game, &Hints::new(config.hints), Duration::ZERO);
+        saved.timestamp = 1_760_000_000;
+        assert_eq!(saved.log_name(), "1760000000-___ann_b.json");
+    }
+}
@@ -0,0 +1,305 @@
+// scores.rs
+// A local JSON score table, shared safely between concurrent games.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Difficulty;
+
+const LEADERBOARD_SIZE: usize = 10;
+
+/// One finished round.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub player: String,
+    pub difficulty: Difficulty,
+    pub won: bool,
+    pub attempts: u32,
+    pub elapsed_ms: u64,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl Record {
+    pub fn new(player: &str, difficulty: Difficulty, won: bool, attempts: u32, elapsed_ms: u64) -> Record {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Record { player: player.to_string(), difficulty, won, attempts, elapsed_ms, timestamp }
+    }
+
+    /// The record's date as `YYYY-MM-DD` (UTC).
+    pub fn date(&self) -> String {
+        let (year, month, day) = civil_from_days((self.timestamp / 86_400) as i64);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Aggregates for a single player across all difficulties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub player: String,
+    pub games: usize,
+    pub wins: usize,
+    /// Average attempts over won games, `None` if the player never won.
+    pub average_attempts: Option<f64>,
+    pub best_streak: usize,
+}
+
+impl PlayerStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+/// The score file on disk. Every access takes an exclusive lock on the file,
+/// so two games finishing at the same moment cannot overwrite each other.
+#[derive(Debug, Clone)]
+pub struct ScoreStore {
+    path: PathBuf,
+}
+
+impl ScoreStore {
+    pub fn new(path: impl Into<PathBuf>) -> ScoreStore {
+        ScoreStore { path: path.into() }
+    }
+
+    /// The store under the user's data directory, e.g.
+    /// `~/.local/share/guessing_game/scores.json`.
+    pub fn open_default() -> io::Result<ScoreStore> {
+        Ok(ScoreStore::new(data_dir()?.join("scores.json")))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> io::Result<Vec<Record>> {
+        let mut file = self.open_locked()?;
+        read_records(&mut file)
+    }
+
+    pub fn append(&self, record: Record) -> io::Result<()> {
+        let mut file = self.open_locked()?;
+        let mut records = read_records(&mut file)?;
+        records.push(record);
+
+        let json = serde_json::to_string_pretty(&records).map_err(io::Error::other)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()
+    }
+
+    fn open_locked(&self) -> io::Result<File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+        // Released when `file` is dropped.
+        file.lock()?;
+        Ok(file)
+    }
+}
+
+fn read_records(file: &mut File) -> io::Result<Vec<Record>> {
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Where the game keeps its files: `$XDG_DATA_HOME/guessing_game`,
+/// falling back to `~/.local/share/guessing_game` (or `%APPDATA%` on Windows).
+pub fn data_dir() -> io::Result<PathBuf> {
+    let base = if let Ok(dir) = env::var("XDG_DATA_HOME") {
+        PathBuf::from(dir)
+    } else if let Ok(home) = env::var("HOME") {
+        Path::new(&home).join(".local").join("share")
+    } else if let Ok(appdata) = env::var("APPDATA") {
+        PathBuf::from(appdata)
+    } else {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "could not find a data directory"));
+    };
+    Ok(base.join("guessing_game"))
+}
+
+/// The best `LEADERBOARD_SIZE` wins for a difficulty: fewest attempts first, then fastest.
+pub fn leaderboard(records: &[Record], difficulty: Difficulty) -> Vec<&Record> {
+    let mut wins: Vec<&Record> = records
+        .iter()
+        .filter(|r| r.won && r.difficulty == difficulty)
+        .collect();
+    wins.sort_by_key(|r| (r.attempts, r.elapsed_ms, r.timestamp));
+    wins.truncate(LEADERBOARD_SIZE);
+    wins
+}
+
+/// Per-player aggregates, sorted by player name.
+pub fn player_stats(records: &[Record]) -> Vec<PlayerStats> {
+    let mut by_player: BTreeMap<&str, Vec<&Record>> = BTreeMap::new();
+    for record in records {
+        by_player.entry(&record.player).or_default().push(record);
+    }
+
+    by_player
+        .into_iter()
+        .map(|(player, mut games)| {
+            games.sort_by_key(|r| r.timestamp);
+
+            let wins: Vec<&&Record> = games.iter().filter(|r| r.won).collect();
+            let average_attempts = if wins.is_empty() {
+                None
+            } else {
+                Some(wins.iter().map(|r| r.attempts as f64).sum::<f64>() / wins.len() as f64)
+            };
+
+            let mut best_streak = 0;
+            let mut streak = 0;
+            for game in &games {
+                streak = if game.won { streak + 1 } else { 0 };
+                best_streak = best_streak.max(streak);
+            }
+
+            PlayerStats {
+                player: player.to_string(),
+                games: games.len(),
+                wins: wins.len(),
+                average_attempts,
+                best_streak,
+            }
+        })
+        .collect()
+}
+
+// Days since 1970-01-01 to a (year, month, day) civil date.
+// Howard Hinnant's algorithm, see http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn record(player: &str, difficulty: Difficulty, won: bool, attempts: u32, timestamp: u64) -> Record {
+        Record { player: player.to_string(), difficulty, won, attempts, elapsed_ms: 1000, timestamp }
+    }
+
+    fn temp_store(name: &str) -> ScoreStore {
+        let dir = env::temp_dir().join(format!("guessing_game_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ScoreStore::new(dir.join("scores.json"))
+    }
+
+    #[test]
+    fn dates_are_formatted_in_utc() {
+        assert_eq!(record("a", Difficulty::Easy, true, 1, 0).date(), "1970-01-01");
+        assert_eq!(record("a", Difficulty::Easy, true, 1, 1_709_208_000).date(), "2024-02-29");
+    }
+
+    #[test]
+    fn leaderboard_ranks_wins_by_attempts() {
+        let mut records = vec![
+            record("ann", Difficulty::Normal, true, 5, 1),
+            record("bob", Difficulty::Normal, true, 3, 2),
+            record("cat", Difficulty::Normal, false, 1, 3),
+            record("dan", Difficulty::Hard, true, 2, 4),
+        ];
+        for i in 0..20 {
+            records.push(record("eve", Difficulty::Normal, true, 6, 10 + i));
+        }
+
+        let board = leaderboard(&records, Difficulty::Normal);
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(board[0].player, "bob");
+        assert_eq!(board[1].player, "ann");
+        assert!(board.iter().all(|r| r.won && r.difficulty == Difficulty::Normal));
+    }
+
+    #[test]
+    fn player_stats_track_rate_average_and_streak() {
+        let records = vec![
+            record("ann", Difficulty::Easy, true, 4, 1),
+            record("ann", Difficulty::Easy, true, 2, 2),
+            record("ann", Difficulty::Easy, false, 10, 3),
+            record("ann", Difficulty::Hard, true, 6, 4),
+            record("bob", Difficulty::Easy, false, 10, 5),
+        ];
+
+        let stats = player_stats(&records);
+        assert_eq!(stats.len(), 2);
+
+        let ann = &stats[0];
+        assert_eq!((ann.games, ann.wins, ann.best_streak), (4, 3, 2));
+        assert_eq!(ann.average_attempts, Some(4.0));
+        assert_eq!(ann.win_rate(), 0.75);
+
+        let bob = &stats[1];
+        assert_eq!(bob.average_attempts, None);
+        assert_eq!(bob.best_streak, 0);
+    }
+
+    #[test]
+    fn store_round_trips_records() {
+        let store = temp_store("round_trip");
+        assert_eq!(store.load().unwrap(), Vec::new());
+
+        let first = record("ann", Difficulty::Easy, true, 3, 1);
+        store.append(first.clone()).unwrap();
+        store.append(record("bob", Difficulty::Hard, false, 10, 2)).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0], first);
+    }
+
+    #[test]
+    fn concurrent_appends_are_not_lost() {
+        let store = temp_store("concurrent");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                thread::spawn(move || {
+                    for j in 0..5 {
+                        store.append(record(&format!("p{}", i), Difficulty::Normal, true, j, 0)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(store.load().unwrap().len(), 40);
+    }
+}
@@ -0,0 +1,120 @@
+// script.rs
+// Non-interactive play: guesses come one per line from a file or a pipe, and
+// every event goes out as one JSON object per line (JSON Lines).
+
+use std::io::{self, BufRead, Write};
+
+use serde::Serialize;
+
+use crate::game::{Feedback, Game, GameState};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Guess { attempt: u32, guess: u32, feedback: Feedback },
+    /// A line that is not a number. It does not cost an attempt.
+    Invalid { line: usize, input: String },
+    End { outcome: Outcome, attempts: u32, secret: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Won,
+    Lost,
+    /// The input ran out before the game was decided.
+    Incomplete,
+}
+
+impl Outcome {
+    /// The process exit code a script run should finish with.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Outcome::Won => 0,
+            Outcome::Lost => 1,
+            Outcome::Incomplete => 3,
+        }
+    }
+}
+
+/// Plays `game` with the guesses in `input`, writing events to `output`.
+/// Blank lines are skipped; anything after the game ends is ignored.
+pub fn run(game: &mut Game, input: impl BufRead, mut output: impl Write) -> io::Result<Outcome> {
+    for (index, line) in input.lines().enumerate() {
+        if game.is_over() {
+            break;
+        }
+        let line = line?;
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let event = match text.parse() {
+            Ok(guess) => {
+                let feedback = game.guess(guess);
+                Event::Guess { attempt: game.attempts(), guess, feedback }
+            }
+            Err(_) => Event::Invalid { line: index + 1, input: text.to_string() },
+        };
+        emit(&mut output, &event)?;
+    }
+
+    let outcome = match game.state() {
+        GameState::Won => Outcome::Won,
+        GameState::OutOfAttempts => Outcome::Lost,
+        GameState::AwaitingGuess => Outcome::Incomplete,
+    };
+    emit(&mut output, &Event::End { outcome, attempts: game.attempts(), secret: game.secret() })?;
+    output.flush()?;
+    Ok(outcome)
+}
+
+fn emit(output: &mut impl Write, event: &Event) -> io::Result<()> {
+    let json = serde_json::to_string(event).map_err(io::Error::other)?;
+    writeln!(output, "{}", json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Fixed;
+
+    fn run_script(game: &mut Game, script: &str) -> (Outcome, Vec<String>) {
+        let mut output = Vec::new();
+        let outcome = run(game, script.as_bytes(), &mut output).unwrap();
+        let lines = String::from_utf8(output).unwrap().lines().map(String::from).collect();
+        (outcome, lines)
+    }
+
+    #[test]
+    fn emits_one_json_object_per_event() {
+        let mut game = Game::new(1..=100, None, &mut Fixed(42));
+        let (outcome, lines) = run_script(&mut game, "50\n\nabc\n42\n99\n");
+
+        assert_eq!(outcome, Outcome::Won);
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"event":"guess","attempt":1,"guess":50,"feedback":"too_big"}"#,
+                r#"{"event":"invalid","line":3,"input":"abc"}"#,
+                r#"{"event":"guess","attempt":2,"guess":42,"feedback":"correct"}"#,
+                r#"{"event":"end","outcome":"won","attempts":2,"secret":42}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn losing_and_running_out_of_input() {
+        let mut game = Game::new(1..=10, Some(2), &mut Fixed(5));
+        let (outcome, lines) = run_script(&mut game, "1\n11\n2\n3\n");
+        assert_eq!(outcome, Outcome::Lost);
+        assert!(lines[1].contains(r#""feedback":"out_of_range""#));
+        assert_eq!(lines.len(), 4);
+        assert_eq!(outcome.exit_code(), 1);
+
+        let mut game = Game::new(1..=10, None, &mut Fixed(5));
+        let (outcome, _) = run_script(&mut game, "1\n");
+        assert_eq!(outcome, Outcome::Incomplete);
+    }
+}
@@ -0,0 +1,248 @@
+// strategy.rs
+// Automatic players. Each one only sees the same feedback a human gets.
+
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng};
+
+use crate::game::Feedback;
+
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+
+    /// Prepares for a new game over `range`.
+    fn reset(&mut self, range: RangeInclusive<u32>);
+
+    fn next_guess(&mut self) -> u32;
+
+    /// Tells the strategy how the game answered its last guess.
+    fn observe(&mut self, guess: u32, feedback: Feedback);
+}
+
+/// The numbers still consistent with all feedback seen so far.
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    low: u32,
+    high: u32,
+}
+
+impl Bounds {
+    fn new(range: RangeInclusive<u32>) -> Bounds {
+        Bounds { low: *range.start(), high: *range.end() }
+    }
+
+    fn narrow(&mut self, guess: u32, feedback: Feedback) {
+        match feedback {
+            Feedback::TooSmall => self.low = self.low.max(guess.saturating_add(1)),
+            Feedback::TooBig => self.high = self.high.min(guess.saturating_sub(1)),
+            _ => {}
+        }
+    }
+
+    /// The point `fraction` of the way from `low` to `high`.
+    fn at(&self, fraction: f64) -> u32 {
+        self.low + ((self.high - self.low) as f64 * fraction) as u32
+    }
+}
+
+/// Always guesses the middle: O(log n).
+pub struct BinarySearch {
+    bounds: Bounds,
+}
+
+impl BinarySearch {
+    pub fn new() -> BinarySearch {
+        BinarySearch { bounds: Bounds::new(0..=0) }
+    }
+}
+
+impl Default for BinarySearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for BinarySearch {
+    fn name(&self) -> &'static str {
+        "binary"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.bounds = Bounds::new(range);
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.bounds.low + (self.bounds.high - self.bounds.low) / 2
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+/// Guesses uniformly at random among the numbers still possible.
+/// Still O(log n) on average, but with a worse constant and a long tail.
+pub struct RandomInBounds {
+    bounds: Bounds,
+    rng: StdRng,
+}
+
+impl RandomInBounds {
+    pub fn new(seed: u64) -> RandomInBounds {
+        // Scramble the seed so a benchmark that draws its secrets from the same seed
+        // doesn't get a strategy that "guesses" the secret in one go.
+        let rng = StdRng::seed_from_u64(seed ^ 0x9e37_79b9_7f4a_7c15);
+        RandomInBounds { bounds: Bounds::new(0..=0), rng }
+    }
+}
+
+impl Strategy for RandomInBounds {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.bounds = Bounds::new(range);
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.rng.gen_range(self.bounds.low..=self.bounds.high)
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+/// Splits the interval at the golden ratio instead of the middle.
+/// Useful to show that an uneven split is still logarithmic, just slower.
+pub struct GoldenSection {
+    bounds: Bounds,
+}
+
+impl GoldenSection {
+    /// 1 - 1/φ ≈ 0.382
+    const SPLIT: f64 = 0.381_966_011_250_105_1;
+
+    pub fn new() -> GoldenSection {
+        GoldenSection { bounds: Bounds::new(0..=0) }
+    }
+}
+
+impl Default for GoldenSection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for GoldenSection {
+    fn name(&self) -> &'static str {
+        "golden"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.bounds = Bounds::new(range);
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.bounds.at(Self::SPLIT)
+    }
+
+    fn observe(&mut self, guess: u32, feedback: Feedback) {
+        self.bounds.narrow(guess, feedback);
+    }
+}
+
+/// Tries every number from the bottom up, ignoring "too big": O(n).
+/// Deliberately bad, as a baseline.
+pub struct LinearScan {
+    next: u32,
+}
+
+impl LinearScan {
+    pub fn new() -> LinearScan {
+        LinearScan { next: 0 }
+    }
+}
+
+impl Default for LinearScan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for LinearScan {
+    fn name(&self) -> &'static str {
+        "linear"
+    }
+
+    fn reset(&mut self, range: RangeInclusive<u32>) {
+        self.next = *range.start();
+    }
+
+    fn next_guess(&mut self) -> u32 {
+        self.next
+    }
+
+    fn observe(&mut self, guess: u32, _feedback: Feedback) {
+        self.next = guess.saturating_add(1);
+    }
+}
+
+/// One of each built-in strategy, in the order the benchmark reports them.
+pub fn all(seed: u64) -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(BinarySearch::new()),
+        Box::new(GoldenSection::new()),
+        Box::new(RandomInBounds::new(seed)),
+        Box::new(LinearScan::new()),
+    ]
+}
+
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    all(seed).into_iter().find(|s| s.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::play_one as play;
+
+    #[test]
+    fn every_strategy_finds_every_secret() {
+        for mut strategy in all(7) {
+            for secret in 1..=50 {
+                play(strategy.as_mut(), 1..=50, secret);
+            }
+        }
+    }
+
+    #[test]
+    fn binary_search_is_logarithmic() {
+        let mut strategy = BinarySearch::new();
+        for secret in 1..=1000 {
+            assert!(play(&mut strategy, 1..=1000, secret) <= 10);
+        }
+    }
+
+    #[test]
+    fn linear_scan_takes_as_many_guesses_as_the_secret() {
+        let mut strategy = LinearScan::new();
+        assert_eq!(play(&mut strategy, 1..=100, 1), 1);
+        assert_eq!(play(&mut strategy, 1..=100, 73), 73);
+    }
+
+    #[test]
+    fn single_number_ranges_work() {
+        for mut strategy in all(1) {
+            assert_eq!(play(strategy.as_mut(), 5..=5, 5), 1);
+        }
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        assert_eq!(by_name("golden", 0).unwrap().name(), "golden");
+        assert!(by_name("psychic", 0).is_none());
+    }
+}
@@ -0,0 +1,357 @@
+// tui.rs
+// Full-screen terminal mode: the whole game is redrawn after every key press
+// and a few times a second so the timer keeps ticking.
+
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+
+use crate::config::Config;
+use crate::game::{Feedback, Game, GameState};
+use crate::hints::{HintKind, Hints};
+use crate::{t, tn};
+
+const BAR_WIDTH: usize = 50;
+const HISTORY_ROWS: usize = 10;
+const MAX_INPUT: usize = 24;
+const TICK: Duration = Duration::from_millis(250);
+
+/// How a TUI session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    /// The game reached a result.
+    Finished,
+    /// The player pressed Esc before the game was over.
+    Quit,
+}
+
+/// Draws the still-possible part of `min..=max` as a filled segment of a bar.
+pub fn range_bar(min: u32, max: u32, low: u32, high: u32, width: usize) -> String {
+    let span = (max - min) as f64 + 1.0;
+    let cell = |n: u32| (((n - min) as f64 / span) * width as f64) as usize;
+
+    let start = cell(low).min(width - 1);
+    let end = if low > high { start } else { (cell(high) + 1).clamp(start + 1, width) };
+
+    (0..width)
+        .map(|i| if i >= start && i < end && low <= high { '█' } else { '░' })
+        .collect()
+}
+
+/// Formats a duration as `mm:ss`.
+pub fn clock(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Restores the terminal when dropped, even if the game panics.
+struct Screen {
+    out: Stdout,
+}
+
+impl Screen {
+    fn enter() -> io::Result<Screen> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen { out })
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Tui<'a> {
+    config: &'a Config,
+    game: &'a mut Game,
+    hints: &'a mut Hints,
+    /// Saves the game; called after every move, as in line mode.
+    checkpoint: &'a dyn Fn(&Game, &Hints),
+    started: Instant,
+    input: String,
+    /// The reply to the last thing the player entered, with its colour.
+    message: Vec<(Color, String)>,
+}
+
+impl Tui<'_> {
+    fn submit(&mut self) {
+        let input = std::mem::take(&mut self.input);
+        let input = input.trim();
+        self.message.clear();
+
+        if let Some(request) = input.strip_prefix("hint") {
+            let reply = match HintKind::parse(request) {
+                Some(kind) => match self.hints.buy(kind, self.game) {
+                    Ok(revealed) => {
+                        (self.checkpoint)(self.game, self.hints);
+                        (Color::Yellow, tn!("hint_bought", self.hints.points(), revealed = revealed))
+                    }
+                    Err(e) => (Color::Red, format!("❌ {}!", e)),
+                },
+                None => (Color::Red, t!("hint_usage")),
+            };
+            self.message.push(reply);
+            return;
+        }
+
+        let guess: u32 = match input.parse() {
+            Ok(num) => num,
+            Err(_) => {
+                self.message.push((Color::Red, t!("invalid_number")));
+                return;
+            }
+        };
+
+        let feedback = self.game.guess(guess);
+        let reply = match feedback {
+            Feedback::TooSmall => (Color::Blue, t!("guess_too_small", guess = guess)),
+            Feedback::TooBig => (Color::Magenta, t!("guess_too_big", guess = guess)),
+            Feedback::Correct => (Color::Green, t!("you_win", secret = self.game.secret())),
+            Feedback::OutOfRange => (
+                Color::Red,
+                t!("out_of_range", guess = guess, min = self.config.min, max = self.config.max),
+            ),
+            Feedback::GameOver => return,
+        };
+        self.message.push(reply);
+
+        // Free hints only follow a counted guess that missed
+        if matches!(feedback, Feedback::TooSmall | Feedback::TooBig) {
+            let free = self.hints.after_guess(guess, self.game);
+            if !free.is_empty() {
+                self.message.push((Color::White, free.to_string()));
+            }
+            if !self.game.is_over() {
+                (self.checkpoint)(self.game, self.hints);
+            }
+        }
+        if self.game.state() == GameState::OutOfAttempts {
+            self.message.push((Color::Red, t!("out_of_attempts", secret = self.game.secret())));
+        }
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut row = 0;
+        let mut line = |out: &mut W, color: Color, text: &str| -> io::Result<()> {
+            queue!(out, cursor::MoveTo(0, row), SetForegroundColor(color), Print(text), ResetColor)?;
+            row += 1;
+            Ok(())
+        };
+
+        queue!(out, terminal::Clear(ClearType::All))?;
+
+        line(
+            out,
+            Color::Cyan,
+            &format!(
+                " {}   {} · {}-{}",
+                t!("guess_the_number"),
+                self.config.difficulty,
+                self.config.min,
+                self.config.max
+            ),
+        )?;
+        line(out, Color::Reset, "")?;
+
+        let feasible = self.game.feasible();
+        line(
+            out,
+            Color::Green,
+            &format!(
+                " [{}]  {}",
+                range_bar(self.config.min, self.config.max, *feasible.start(), *feasible.end(), BAR_WIDTH),
+                t!("still_possible", low = feasible.start(), high = feasible.end())
+            ),
+        )?;
+
+        let attempts = match (self.game.attempts_left(), self.game.max_attempts()) {
+            (Some(left), Some(max)) => t!("attempts_of", left = left, max = max),
+            _ => t!("attempts_count", count = self.game.attempts()),
+        };
+        let score = if self.config.hints.budget > 0 {
+            format!("   {}", t!("tui_score", points = self.hints.points()))
+        } else {
+            String::new()
+        };
+        line(
+            out,
+            Color::Reset,
+            &format!(" {}   ⏱  {}{}", attempts, clock(self.started.elapsed()), score),
+        )?;
+        line(out, Color::Reset, "")?;
+
+        line(out, Color::Reset, &format!(" {}", t!("history")))?;
+        let history = self.game.history();
+        let skip = history.len().saturating_sub(HISTORY_ROWS);
+        for (i, &(guess, feedback)) in history.iter().enumerate().skip(skip) {
+            let (color, marker) = match feedback {
+                Feedback::TooSmall => (Color::Blue, t!("marker_higher")),
+                Feedback::TooBig => (Color::Magenta, t!("marker_lower")),
+                _ => (Color::Green, t!("marker_correct")),
+            };
+            line(out, color, &format!("   {:>2}. {:>6}  {}", i + 1, guess, marker))?;
+        }
+        line(out, Color::Reset, "")?;
+
+        for (color, text) in &self.message {
+            line(out, *color, &format!(" {}", text))?;
+        }
+        line(out, Color::Reset, "")?;
+
+        if self.game.is_over() {
+            line(out, Color::DarkGrey, &format!(" {}", t!("press_any_key")))?;
+        } else {
+            line(out, Color::Reset, &format!(" > {}_", self.input))?;
+            line(out, Color::Reset, "")?;
+            line(out, Color::DarkGrey, &format!(" {}", t!("tui_help")))?;
+        }
+
+        out.flush()
+    }
+}
+
+/// Plays `game` full-screen until it ends or the player presses Esc,
+/// calling `checkpoint` after every guess or hint. The clock counts from
+/// `started`, which is in the past for a resumed game.
+pub fn play(
+    config: &Config,
+    game: &mut Game,
+    hints: &mut Hints,
+    started: Instant,
+    checkpoint: &dyn Fn(&Game, &Hints),
+) -> io::Result<Ending> {
+    let mut screen = Screen::enter()?;
+    let mut tui = Tui {
+        config,
+        game,
+        hints,
+        checkpoint,
+        started,
+        input: String::new(),
+        message: Vec::new(),
+    };
+
+    loop {
+        tui.draw(&mut screen.out)?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if tui.game.is_over() {
+            return Ok(Ending::Finished);
+        }
+
+        let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Ok(Ending::Quit),
+            _ if ctrl_c => return Ok(Ending::Quit),
+            KeyCode::Enter => tui.submit(),
+            KeyCode::Backspace => {
+                tui.input.pop();
+            }
+            KeyCode::Char(c) if tui.input.chars().count() < MAX_INPUT => tui.input.push(c),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bar_shrinks_with_the_feasible_range() {
+        assert_eq!(range_bar(1, 100, 1, 100, 10), "██████████");
+        assert_eq!(range_bar(1, 100, 51, 100, 10), "░░░░░█████");
+        assert_eq!(range_bar(1, 100, 1, 10, 10), "█░░░░░░░░░");
+    }
+
+    #[test]
+    fn a_single_number_still_shows_up() {
+        let bar = range_bar(1, 1000, 500, 500, 20);
+        assert_eq!(bar.chars().filter(|&c| c == '█').count(), 1);
+        assert_eq!(range_bar(1, 1000, 1000, 1000, 20).chars().last(), Some('█'));
+    }
+
+    #[test]
+    fn clock_formats_minutes_and_seconds() {
+        assert_eq!(clock(Duration::from_secs(0)), "00:00");
+        assert_eq!(clock(Duration::from_secs(75)), "01:15");
+    }
+
+    #[test]
+    fn submitting_guesses_and_hints() {
+        let config = Config::custom(1, 100, Some(5)).unwrap();
+        let mut game = Game::from_config(&config, &mut crate::game::Fixed(42));
+        let mut hints = Hints::new(config.hints);
+        let saves = std::cell::Cell::new(0);
+        let checkpoint = |_: &Game, _: &Hints| saves.set(saves.get() + 1);
+        let mut tui = Tui {
+            config: &config,
+            game: &mut game,
+            hints: &mut hints,
+            checkpoint: &checkpoint,
+            started: Instant::now(),
+            input: String::from("50"),
+            message: Vec::new(),
+        };
+
+        tui.submit();
+        assert!(tui.message[0].1.contains("too big"));
+        assert_eq!(tui.message[1].1, "🌤️  Warm.");
+        assert!(tui.input.is_empty());
+
+        tui.input = String::from("500");
+        tui.submit();
+        assert_eq!(tui.message.len(), 1, "no free hints after an out-of-range guess");
+
+        tui.input = String::from("hint parity");
+        tui.submit();
+        assert_eq!(tui.message[0].1, "💡 The number is even. (15 points left)");
+
+        tui.input = String::from("42");
+        tui.submit();
+        assert!(tui.game.is_over());
+        // Saved after the miss and the hint; the out-of-range guess and the win changed nothing worth saving
+        assert_eq!(saves.get(), 2);
+
+        let mut screen = Vec::new();
+        tui.draw(&mut screen).unwrap();
+        let screen = String::from_utf8_lossy(&screen);
+        assert!(screen.contains("42-42 still possible"));
+        assert!(screen.contains("✔ correct"));
+    }
+
+    #[test]
+    fn resumed_games_keep_their_time() {
+        let config = Config::custom(1, 100, None).unwrap();
+        let mut game = Game::from_config(&config, &mut crate::game::Fixed(42));
+        let mut hints = Hints::new(config.hints);
+        let tui = Tui {
+            config: &config,
+            game: &mut game,
+            hints: &mut hints,
+            checkpoint: &|_, _| {},
+            started: Instant::now() - Duration::from_secs(75),
+            input: String::new(),
+            message: Vec::new(),
+        };
+
+        let mut screen = Vec::new();
+        tui.draw(&mut screen).unwrap();
+        assert!(String::from_utf8_lossy(&screen).contains("⏱  01:15"));
+    }
+}
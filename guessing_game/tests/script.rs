@@ -0,0 +1,78 @@
+// Drives the real binary the way a bot or a grader would: guesses on stdin,
+// JSON Lines on stdout, the outcome in the exit code.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run(args: &[&str], input: &str) -> (i32, Vec<String>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start guessing_game");
+
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (output.status.code().unwrap(), stdout.lines().map(String::from).collect())
+}
+
+fn every_number(max: u32) -> String {
+    (1..=max).map(|n| format!("{}\n", n)).collect()
+}
+
+#[test]
+fn scanning_the_range_wins_with_exit_code_zero() {
+    let (code, lines) = run(&["--min", "1", "--max", "50", "--attempts", "50", "--seed", "3", "--script", "-"], &every_number(50));
+
+    assert_eq!(code, 0);
+    let end = lines.last().unwrap();
+    assert!(end.starts_with(r#"{"event":"end","outcome":"won""#), "{}", end);
+}
+
+#[test]
+fn the_same_seed_gives_the_same_game() {
+    let args = ["--hard", "--seed", "1234", "--script", "-"];
+    let first = run(&args, "500\n250\n750\n");
+    let second = run(&args, "500\n250\n750\n");
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn losing_and_incomplete_games_have_their_own_exit_codes() {
+    let args = ["--max", "100", "--attempts", "1", "--seed", "9", "--script", "-"];
+
+    // No guesses at all: the end event still reveals the secret.
+    let (code, lines) = run(&args, "");
+    assert_eq!(code, 3);
+    assert!(lines[0].contains(r#""outcome":"incomplete""#));
+
+    let secret: u32 = lines[0].rsplit(':').next().unwrap().trim_end_matches('}').parse().unwrap();
+    let wrong = if secret == 1 { 2 } else { 1 };
+
+    let (code, lines) = run(&args, &format!("{}\n", wrong));
+    assert_eq!(code, 1);
+    assert!(lines.last().unwrap().contains(r#""outcome":"lost""#));
+}
+
+#[test]
+fn piped_input_is_a_script_even_without_the_flag() {
+    let (code, lines) = run(&["--min", "1", "--max", "50", "--attempts", "50", "--seed", "3"], &every_number(50));
+
+    assert_eq!(code, 0);
+    assert!(lines.iter().all(|line| line.starts_with(r#"{"event":"#)), "{:?}", lines);
+
+    let (code, lines) = run(&["--resume"], "");
+    assert_eq!(code, 2);
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn missing_script_file_is_a_usage_error() {
+    let (code, lines) = run(&["--script", "/no/such/file"], "");
+    assert_eq!(code, 2);
+    assert!(lines.is_empty());
+}
@@ -0,0 +1,3 @@
+// lib.rs
+// The numbered lessons in ../examples are this crate's examples, e.g.
+// `cargo run --example 012-file-io`. There is no library code of its own.
@@ -0,0 +1,346 @@
+// golden.rs
+// Snapshot tests for the numbered lessons: every file in ../examples is
+// compiled, run, and what it prints is compared with tests/golden/<name>.txt.
+//
+//   cargo test -p learning --test golden               # check every example
+//   cargo test -p learning --test golden -- 008        # only the ones matching "008"
+//   cargo test -p learning --test golden -- --update   # accept the new output
+//
+// Some output changes from run to run (HashMap order, the current directory,
+// addresses, timings), so both sides are normalized before comparing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+
+/// Examples get this instead of the caller's PATH, so anything they print
+/// about it (its length, its entries) is the same on every machine.
+const PINNED_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+
+/// Lines indented under these headers come from looping over a HashMap or a
+/// directory listing, so they are compared without regard to order.
+const UNORDERED: &[(&str, &str)] = &[
+    ("008-collections", "All students:"),
+    ("008-collections", "Words grouped by first letter:"),
+    ("012-file-io", "Directory contents:"),
+    ("012-file-io", "Recursive directory listing:"),
+];
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let update = args.iter().any(|arg| arg == "--update");
+    let filters: Vec<&String> = args.iter().filter(|arg| !arg.starts_with('-')).collect();
+
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let golden_dir = manifest_dir.join("tests").join("golden");
+    let work_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+
+    let examples: Vec<PathBuf> = examples(&manifest_dir.join("../examples"))
+        .into_iter()
+        .filter(|path| filters.is_empty() || filters.iter().any(|f| name(path).contains(f.as_str())))
+        .collect();
+
+    // Compiling is the slow part, so every example gets its own thread.
+    let results: Vec<(String, Result<String, String>)> = thread::scope(|scope| {
+        let handles: Vec<_> = examples
+            .iter()
+            .map(|path| scope.spawn(|| (name(path), transcript(path, &work_dir))))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    println!("\nrunning {} examples", results.len());
+    let mut failed = Vec::new();
+    for (name, result) in &results {
+        let golden_path = golden_dir.join(format!("{}.txt", name));
+        let outcome = match result {
+            Err(e) => Err(e.clone()),
+            Ok(actual) if update => {
+                fs::create_dir_all(&golden_dir).unwrap();
+                fs::write(&golden_path, actual).unwrap();
+                Ok("updated")
+            }
+            Ok(actual) => match fs::read_to_string(&golden_path) {
+                Ok(expected) if expected == *actual => Ok("ok"),
+                Ok(expected) => Err(first_difference(&expected, actual)),
+                Err(_) => Err(format!("no {} yet; run with --update to create it", golden_path.display())),
+            },
+        };
+        match outcome {
+            Ok(status) => println!("example {} ... {}", name, status),
+            Err(e) => {
+                println!("example {} ... FAILED", name);
+                failed.push((name, e));
+            }
+        }
+    }
+
+    for (name, e) in &failed {
+        println!("\n---- {} ----\n{}", name, e);
+    }
+    if !failed.is_empty() {
+        println!("\nIf the new output is right, run: cargo test -p learning --test golden -- --update");
+    }
+    let verdict = if failed.is_empty() { "ok" } else { "FAILED" };
+    println!("\ntest result: {}. {} passed; {} failed\n", verdict, results.len() - failed.len(), failed.len());
+
+    if !failed.is_empty() {
+        process::exit(1);
+    }
+}
+
+/// Every `.rs` file in `dir`, sorted by name (which is lesson order).
+fn examples(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn name(path: &Path) -> String {
+    path.file_stem().unwrap().to_string_lossy().into_owned()
+}
+
+/// Compiles and runs one example in an empty directory of its own, and
+/// returns its normalized output.
+fn transcript(source: &Path, work_dir: &Path) -> Result<String, String> {
+    let name = name(source);
+    let bin_dir = work_dir.join("bin");
+    let run_dir = work_dir.join("run").join(&name);
+    let _ = fs::remove_dir_all(&run_dir);
+    fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&run_dir).map_err(|e| e.to_string())?;
+    // Symlinked temp directories (macOS) would otherwise print a different path.
+    let run_dir = run_dir.canonicalize().map_err(|e| e.to_string())?;
+    let binary = bin_dir.join(&name);
+
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let compiled = Command::new(rustc)
+        .args(["--edition", "2021", "--crate-name", "example", "-A", "warnings", "-o"])
+        .arg(&binary)
+        .arg(source)
+        .output()
+        .map_err(|e| format!("Could not run rustc: {}", e))?;
+    if !compiled.status.success() {
+        return Err(format!("does not compile:\n{}", String::from_utf8_lossy(&compiled.stderr)));
+    }
+
+    let run = Command::new(&binary)
+        .current_dir(&run_dir)
+        .env_clear()
+        .env("PATH", PINNED_PATH)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run {}: {}", binary.display(), e))?;
+
+    let clean = |bytes: &[u8]| {
+        let text = String::from_utf8_lossy(bytes)
+            .replace(&*binary.to_string_lossy(), "[EXE]")
+            .replace(&*run_dir.to_string_lossy(), "[CWD]");
+        normalize(&name, &text)
+    };
+    let mut transcript = clean(&run.stdout);
+    let stderr = clean(&run.stderr);
+    if !stderr.is_empty() {
+        transcript.push_str("--- stderr ---\n");
+        transcript.push_str(&stderr);
+    }
+    if !run.status.success() {
+        return Err(format!("exited with {}:\n{}", run.status, transcript));
+    }
+    Ok(transcript)
+}
+
+/// Makes the parts of `text` that change between runs stable.
+fn normalize(example: &str, text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(|line| sort_sets(&redact(line.trim_end()))).collect();
+
+    let headers: Vec<&str> = UNORDERED.iter().filter(|(name, _)| *name == example).map(|(_, h)| *h).collect();
+    let mut normalized = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        normalized.push(lines[i].clone());
+        i += 1;
+        if headers.contains(&lines[i - 1].trim()) {
+            let block = unordered_block(&lines[i..]);
+            i += block.len();
+            normalized.extend(sort_groups(block));
+        }
+    }
+
+    let mut text = normalized.join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text
+}
+
+/// Replaces addresses, timestamps and durations with placeholders.
+fn redact(line: &str) -> String {
+    line.split(' ')
+        .map(|word| {
+            let core = word.trim_matches(|c: char| matches!(c, ',' | ';' | '(' | ')' | '[' | ']' | '"'));
+            match placeholder(core) {
+                Some(placeholder) if !core.is_empty() => word.replacen(core, placeholder, 1),
+                _ => word.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn placeholder(word: &str) -> Option<&'static str> {
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+    if let Some(hex) = word.strip_prefix("0x") {
+        if hex.len() >= 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some("[ADDR]");
+        }
+    }
+    // 2026-10-17, 2026-10-17T12:30:00Z
+    let date: Vec<&str> = word.split(['T', ' ']).next()?.split('-').collect();
+    if date.len() == 3 && date.iter().all(|part| is_digits(part)) && date[0].len() == 4 {
+        return Some("[TIMESTAMP]");
+    }
+    // 12:30:00, 12:30:00.123
+    let clock: Vec<&str> = word.split(':').collect();
+    if clock.len() == 3 && is_digits(clock[0]) && is_digits(clock[1]) && is_number(clock[2]) {
+        return Some("[TIMESTAMP]");
+    }
+    // Debug-printed `Duration`s: 12.5ms, 3µs, 1.002s
+    for unit in ["ns", "µs", "us", "ms", "s"] {
+        if word.strip_suffix(unit).is_some_and(|n| is_number(n) && n.contains(|c: char| c.is_ascii_digit())) {
+            return Some("[DURATION]");
+        }
+    }
+    None
+}
+
+/// Sorts the elements of every `{a, b, c}` in `line`, which is how HashMaps
+/// and HashSets print with `{:?}`. Struct fields (`Point { x: 1, y: 2 }`)
+/// have spaces inside the braces and are left alone.
+fn sort_sets(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open..];
+        match closing_brace(after) {
+            Some(close) if !after[1..].starts_with([' ', '}']) && !after[..close].ends_with(' ') => {
+                let mut items = split_top_level(&after[1..close]);
+                items.sort();
+                out.push('{');
+                out.push_str(&items.join(", "));
+                out.push('}');
+                rest = &after[close + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The index of the `}` matching the `{` that `text` starts with.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' | '[' | '(' if !in_string => depth += 1,
+            '}' | ']' | ')' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return (c == '}').then_some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits `a, [b, c], "d, e"` at the commas that are not nested or quoted.
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' | '[' | '(' if !in_string => depth += 1,
+            '}' | ']' | ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                items.push(sort_sets(text[start..i].trim()));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(sort_sets(text[start..].trim()));
+    items
+}
+
+/// The lines right after a header that are indented at least as far as the
+/// first of them, up to the next blank line.
+fn unordered_block(lines: &[String]) -> &[String] {
+    let Some(first) = lines.first() else { return lines };
+    let base = indent(first);
+    let len = lines.iter().take_while(|line| !line.is_empty() && indent(line) >= base).count();
+    &lines[..len]
+}
+
+/// Sorts a block as groups: a line at the block's own indentation together
+/// with the more deeply indented lines under it.
+fn sort_groups(block: &[String]) -> Vec<String> {
+    let Some(first) = block.first() else { return Vec::new() };
+    let base = indent(first);
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    for line in block {
+        match groups.last_mut() {
+            Some(group) if indent(line) > base => group.push(line.clone()),
+            _ => groups.push(vec![line.clone()]),
+        }
+    }
+    groups.sort();
+    groups.concat()
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut number = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => number += 1,
+            (e, a) => {
+                return format!(
+                    "output differs at line {}:\n  expected: {}\n  actual:   {}",
+                    number,
+                    e.unwrap_or("<end of output>"),
+                    a.unwrap_or("<end of output>")
+                );
+            }
+        }
+    }
+}
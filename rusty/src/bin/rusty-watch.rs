@@ -0,0 +1,121 @@
+// rusty-watch.rs
+// Watches exercises/ and re-checks an exercise every time it is saved. Once
+// the current exercise passes, moves straight on to the next one.
+
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use rusty::manifest::find_root;
+use rusty::progress::progress_path;
+use rusty::runner;
+use rusty::watch::{self, CLEAR};
+use rusty::{Exercise, Manifest, Progress};
+
+/// Editors often save in several steps; wait this long for the dust to settle.
+const SETTLE: Duration = Duration::from_millis(200);
+
+fn main() {
+    if env::args().len() > 1 {
+        eprintln!("Usage: rusty-watch");
+        eprintln!("Checks the next exercise every time a file in exercises/ is saved.");
+        process::exit(2);
+    }
+
+    let root = match env::current_dir().and_then(|dir| find_root(&dir)) {
+        Ok(root) => root,
+        Err(e) => fail(&format!("Could not find the exercises: {}", e)),
+    };
+    let manifest = Manifest::load(&root).unwrap_or_else(|e| fail(&e));
+    let mut progress = Progress::load(&progress_path(&root))
+        .unwrap_or_else(|e| fail(&format!("Could not read your progress: {}", e)));
+
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)
+        .unwrap_or_else(|e| fail(&format!("Could not start watching: {}", e)));
+    watcher
+        .watch(&root.join("exercises"), RecursiveMode::NonRecursive)
+        .unwrap_or_else(|e| fail(&format!("Could not watch exercises/: {}", e)));
+
+    let Some(mut current) = progress.next(&manifest) else {
+        println!("🎉 Every exercise is done!");
+        return;
+    };
+    check(&root, &manifest, &mut progress, current);
+
+    while let Some(paths) = next_batch(&events) {
+        for exercise in watch::changed_exercises(&manifest, &progress, &paths) {
+            let passed = check(&root, &manifest, &mut progress, exercise);
+            if !passed || exercise.name != current.name {
+                continue;
+            }
+
+            match progress.next(&manifest) {
+                Some(next) => {
+                    println!("\n🎉 {} is done! Moving on to {}...", current.name, next.name);
+                    std::thread::sleep(Duration::from_secs(2));
+                    current = next;
+                    check(&root, &manifest, &mut progress, current);
+                }
+                None => {
+                    println!("\n🎉 Every exercise is done! Well done.");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("❌ {}", message);
+    process::exit(2);
+}
+
+/// Checks an exercise, redraws the screen and records the result.
+fn check(root: &Path, manifest: &Manifest, progress: &mut Progress, exercise: &Exercise) -> bool {
+    print!("{}🔍 Checking {}...", CLEAR, exercise.name);
+    let _ = io::stdout().flush();
+    let report = match runner::check(root, exercise) {
+        Ok(report) => report,
+        Err(e) => {
+            println!("\n❌ Could not check {}: {}", exercise.name, e);
+            return false;
+        }
+    };
+
+    let passed = report.verdict.passed();
+    progress.record(exercise, passed);
+    if let Err(e) = progress.save(&progress_path(root)) {
+        eprintln!("⚠️  Could not save your progress: {}", e);
+    }
+
+    let done = progress.count(manifest);
+    print!("{}{}", CLEAR, watch::render(exercise, &report, done, manifest.exercises.len()));
+    passed
+}
+
+/// Waits for files to be written, then gathers everything else that changes
+/// shortly after. `None` once the watcher has gone away.
+fn next_batch(events: &Receiver<notify::Result<Event>>) -> Option<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    collect(events.recv().ok()?, &mut paths);
+    while let Ok(event) = events.recv_timeout(SETTLE) {
+        collect(event, &mut paths);
+    }
+    Some(paths)
+}
+
+fn collect(event: notify::Result<Event>, paths: &mut Vec<PathBuf>) {
+    match event {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+            paths.extend(event.paths);
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("⚠️  {}", e),
+    }
+}
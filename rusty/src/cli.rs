@@ -0,0 +1,80 @@
+// cli.rs
+// Turns command-line arguments into the thing the binary should do.
+
+pub const USAGE: &str = "\
+Usage: rusty <command>
+
+Commands:
+  list                 show every exercise and whether it is done
+  run [exercise]       compile and check one exercise (default: the next one to do)
+  verify               check the exercises in order, stopping at the first one
+                       that does not pass yet
+  hint [exercise]      show the hint for an exercise (default: the next one to do)
+  quiz [count]         answer the flashcards that are due for review (default: 10)
+                       and see which example to read again";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    List,
+    /// `None` means the next exercise that is not done.
+    Run(Option<String>),
+    Verify,
+    Hint(Option<String>),
+    /// How many questions to ask at most.
+    Quiz(usize),
+}
+
+/// Parses the arguments that follow the program name.
+pub fn parse<I, S>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let args: Vec<String> = args.into_iter().map(|arg| arg.as_ref().to_string()).collect();
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(String::from("Missing command")),
+    };
+
+    let command = match (command, rest) {
+        ("list", []) => Command::List,
+        ("verify", []) => Command::Verify,
+        ("run", [] | [_]) => Command::Run(rest.first().cloned()),
+        ("hint", [] | [_]) => Command::Hint(rest.first().cloned()),
+        ("quiz", []) => Command::Quiz(10),
+        ("quiz", [count]) => match count.parse() {
+            Ok(count) if count > 0 => Command::Quiz(count),
+            _ => return Err(format!("Not a number of questions: {}", count)),
+        },
+        ("list" | "verify" | "run" | "hint" | "quiz", _) => {
+            return Err(format!("Too many arguments for {}", command));
+        }
+        _ => return Err(format!("Unknown command: {}", command)),
+    };
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_and_their_arguments() {
+        assert_eq!(parse(["list"]), Ok(Command::List));
+        assert_eq!(parse(["verify"]), Ok(Command::Verify));
+        assert_eq!(parse(["run"]), Ok(Command::Run(None)));
+        assert_eq!(parse(["hint", "ex1_variables"]), Ok(Command::Hint(Some(String::from("ex1_variables")))));
+        assert_eq!(parse(["quiz"]), Ok(Command::Quiz(10)));
+        assert_eq!(parse(["quiz", "3"]), Ok(Command::Quiz(3)));
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert!(parse::<[&str; 0], &str>([]).is_err());
+        assert!(parse(["watch"]).is_err());
+        assert!(parse(["list", "extra"]).is_err());
+        assert!(parse(["run", "a", "b"]).is_err());
+        assert!(parse(["quiz", "0"]).is_err());
+        assert!(parse(["quiz", "many"]).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+// lib.rs
+// A small exercise runner in the spirit of rustlings: exercises are listed in
+// exercises/info.toml and checked one after another.
+
+pub mod cli;
+pub mod manifest;
+pub mod progress;
+pub mod quiz;
+pub mod review;
+pub mod runner;
+pub mod watch;
+
+pub use manifest::{Exercise, Manifest, Mode};
+pub use progress::Progress;
+pub use quiz::{Bank, Question};
+pub use review::Review;
+pub use runner::{Report, Verdict};
@@ -0,0 +1,211 @@
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process;
+
+use rusty::cli::{self, Command};
+use rusty::manifest::find_root;
+use rusty::progress::progress_path;
+use rusty::review::{self, review_path};
+use rusty::runner;
+use rusty::{Bank, Exercise, Manifest, Progress, Question, Review};
+
+fn main() {
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let root = match env::current_dir().and_then(|dir| find_root(&dir)) {
+        Ok(root) => root,
+        Err(e) => fail(&format!("Could not find the exercises: {}", e)),
+    };
+    let manifest = Manifest::load(&root).unwrap_or_else(|e| fail(&e));
+    let mut progress = Progress::load(&progress_path(&root))
+        .unwrap_or_else(|e| fail(&format!("Could not read your progress: {}", e)));
+
+    match command {
+        Command::List => list(&manifest, &progress),
+        Command::Run(name) => {
+            let exercise = pick(&manifest, &progress, name.as_deref());
+            if !run(&root, exercise, &mut progress) {
+                process::exit(1);
+            }
+        }
+        Command::Verify => verify(&root, &manifest, &mut progress),
+        Command::Hint(name) => {
+            let exercise = pick(&manifest, &progress, name.as_deref());
+            println!("💡 {}: {}", exercise.name, exercise.hint.trim());
+        }
+        Command::Quiz(count) => quiz(&root, count),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("❌ {}", message);
+    process::exit(2);
+}
+
+/// The named exercise, or the next one to do.
+fn pick<'a>(manifest: &'a Manifest, progress: &Progress, name: Option<&str>) -> &'a Exercise {
+    match name {
+        Some(name) => manifest
+            .find(name)
+            .unwrap_or_else(|| fail(&format!("No exercise called {} (see `rusty list`)", name))),
+        None => progress.next(manifest).unwrap_or_else(|| {
+            println!("🎉 Every exercise is done!");
+            process::exit(0);
+        }),
+    }
+}
+
+fn list(manifest: &Manifest, progress: &Progress) {
+    println!("📚 Exercises ({}/{} done)", progress.count(manifest), manifest.exercises.len());
+    let next = progress.next(manifest).map(|exercise| exercise.name.as_str());
+
+    for exercise in &manifest.exercises {
+        let mark = if progress.is_done(&exercise.name) {
+            "✅"
+        } else if Some(exercise.name.as_str()) == next {
+            "👉"
+        } else {
+            "⬜"
+        };
+        println!("  {} {:<26} {}", mark, exercise.name, exercise.path.display());
+    }
+}
+
+/// Checks one exercise, prints what happened and records the result.
+/// Returns whether it passed.
+fn run(root: &Path, exercise: &Exercise, progress: &mut Progress) -> bool {
+    println!("🔍 Checking {} ({})...", exercise.name, exercise.path.display());
+    let report = match runner::check(root, exercise) {
+        Ok(report) => report,
+        Err(e) => fail(&format!("Could not check {}: {}", exercise.name, e)),
+    };
+
+    if !report.verdict.passed() && !report.output.trim().is_empty() {
+        println!("{}", report.output.trim_end());
+    }
+    println!("{}", report.verdict);
+
+    let passed = report.verdict.passed();
+    progress.record(exercise, passed);
+    if let Err(e) = progress.save(&progress_path(root)) {
+        eprintln!("⚠️  Could not save your progress: {}", e);
+    }
+    passed
+}
+
+fn verify(root: &Path, manifest: &Manifest, progress: &mut Progress) {
+    for exercise in &manifest.exercises {
+        if !progress.needs_check(exercise) {
+            println!("✅ {} (done; the stage after it checks this file now)", exercise.name);
+            continue;
+        }
+        if !run(root, exercise, progress) {
+            println!("💡 Stuck? Try: rusty hint {}", exercise.name);
+            process::exit(1);
+        }
+    }
+    println!("🎉 All {} exercises pass!", manifest.exercises.len());
+}
+
+/// Asks up to `count` flashcards that are due, then shows how each topic is going.
+fn quiz(root: &Path, count: usize) {
+    let bank = Bank::load(root).unwrap_or_else(|e| fail(&e));
+    let path = review_path(root);
+    let mut review =
+        Review::load(&path).unwrap_or_else(|e| fail(&format!("Could not read your quiz history: {}", e)));
+    let today = review::today();
+
+    let questions = review.due(&bank, today, count);
+    if questions.is_empty() {
+        if let Some(day) = review.next_due(today) {
+            println!("🎉 Nothing to review today. Come back in {} day(s).", day - today);
+        }
+        report(&bank, &review);
+        return;
+    }
+
+    println!("🧠 {} question(s) to review. Answer with a number, or q to stop.", questions.len());
+    let mut lines = io::stdin().lock().lines();
+    let (mut asked, mut right) = (0, 0);
+    for (index, question) in questions.iter().enumerate() {
+        println!("\n[{}/{}] {}", index + 1, questions.len(), question.topic);
+        show(question);
+        let Some(choice) = ask(&mut lines, question.choices.len()) else { break };
+
+        let correct = question.choices[choice] == question.answer;
+        asked += 1;
+        if correct {
+            right += 1;
+            println!("✅ Right!");
+        } else {
+            println!("❌ The answer is: {}", question.answer);
+            if let Some(explanation) = &question.explanation {
+                println!("💡 {}", explanation.trim());
+            }
+        }
+
+        review.record(question, correct, today);
+        if let Err(e) = review.save(&path) {
+            eprintln!("⚠️  Could not save your quiz history: {}", e);
+        }
+    }
+
+    if asked > 0 {
+        println!("\n📊 {}/{} right this time", right, asked);
+    }
+    report(&bank, &review);
+}
+
+fn show(question: &Question) {
+    if let Some(code) = &question.code {
+        println!();
+        for line in code.trim_matches('\n').lines() {
+            println!("    {}", line);
+        }
+        println!();
+    }
+    println!("{}", question.prompt);
+    for (number, choice) in question.choices.iter().enumerate() {
+        println!("  {}) {}", number + 1, choice);
+    }
+}
+
+/// The index of the choice picked, or `None` to stop.
+fn ask(lines: &mut impl Iterator<Item = io::Result<String>>, choices: usize) -> Option<usize> {
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let line = lines.next()?.ok()?;
+        match line.trim() {
+            "q" | "quit" => return None,
+            answer => match answer.parse::<usize>() {
+                Ok(number) if (1..=choices).contains(&number) => return Some(number - 1),
+                _ => println!("Type a number from 1 to {}, or q to stop", choices),
+            },
+        }
+    }
+}
+
+/// Accuracy per topic, and the example to read again for the weakest one.
+fn report(bank: &Bank, review: &Review) {
+    let tallies = review.tallies();
+    if tallies.is_empty() {
+        return;
+    }
+    println!("\n🎯 Accuracy by topic (weakest first):");
+    for (topic, tally) in tallies {
+        let percent = (tally.accuracy() * 100.0).round();
+        println!("  {:<24} {:>3}%  ({}/{})", topic, percent, tally.right, tally.asked);
+    }
+    if let Some(topic) = review.weakest(bank) {
+        println!("\n📖 Go over {} again: cargo run --example {}", topic.name, topic.example);
+    }
+}
@@ -0,0 +1,157 @@
+// manifest.rs
+// The list of exercises, in the order they should be done, read from
+// exercises/info.toml.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+pub const MANIFEST: &str = "exercises/info.toml";
+
+/// How an exercise is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// It compiles, and running it succeeds.
+    Compile,
+    /// Its `#[test]`s pass.
+    Test,
+    /// Running it prints `expected_output`.
+    Output,
+    /// rustc rejects it with `expected_error` and nothing else.
+    CompileFail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Exercise {
+    pub name: String,
+    /// Relative to the repository root.
+    pub path: PathBuf,
+    pub mode: Mode,
+    pub hint: String,
+    #[serde(default)]
+    pub expected_output: Option<String>,
+    /// An error code such as `E0382`.
+    #[serde(default)]
+    pub expected_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Manifest {
+    pub exercises: Vec<Exercise>,
+}
+
+impl Manifest {
+    pub fn parse(source: &str) -> Result<Manifest, String> {
+        let manifest: Manifest = toml::from_str(source).map_err(|e| e.to_string())?;
+
+        let mut names = HashSet::new();
+        for exercise in &manifest.exercises {
+            if !names.insert(exercise.name.as_str()) {
+                return Err(format!("Exercise {} is listed twice", exercise.name));
+            }
+            if exercise.mode == Mode::Output && exercise.expected_output.is_none() {
+                return Err(format!("Exercise {} needs an expected_output", exercise.name));
+            }
+            if exercise.mode == Mode::CompileFail && !exercise.expected_error.as_deref().is_some_and(is_error_code) {
+                return Err(format!("Exercise {} needs an expected_error like \"E0382\"", exercise.name));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn load(root: &Path) -> Result<Manifest, String> {
+        let path = root.join(MANIFEST);
+        let source = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        Manifest::parse(&source).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Exercise> {
+        self.exercises.iter().find(|exercise| exercise.name == name)
+    }
+}
+
+fn is_error_code(code: &str) -> bool {
+    code.len() == 5 && code.starts_with('E') && code[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// The closest directory at or above `start` that has an exercise manifest.
+pub fn find_root(start: &Path) -> io::Result<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(MANIFEST).is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            let message = format!("no {} here or in any parent directory", MANIFEST);
+            io::Error::new(io::ErrorKind::NotFound, message)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+[[exercises]]
+name = "ex1"
+path = "exercises/ex1.rs"
+mode = "compile"
+hint = "Look at the TODOs."
+
+[[exercises]]
+name = "ex2"
+path = "exercises/ex2.rs"
+mode = "output"
+hint = "Print it."
+expected_output = "hello"
+
+[[exercises]]
+name = "ex3"
+path = "exercises/ex3.rs"
+mode = "compile_fail"
+hint = "Move it, then use it."
+expected_error = "E0382"
+"#;
+
+    #[test]
+    fn parses_exercises_in_order() {
+        let manifest = Manifest::parse(SAMPLE).unwrap();
+        let names: Vec<_> = manifest.exercises.iter().map(|e| e.name.as_str()).collect();
+
+        assert_eq!(names, ["ex1", "ex2", "ex3"]);
+        assert_eq!(manifest.find("ex2").unwrap().expected_output.as_deref(), Some("hello"));
+        assert_eq!(manifest.find("ex1").unwrap().mode, Mode::Compile);
+        assert_eq!(manifest.find("ex3").unwrap().mode, Mode::CompileFail);
+        assert_eq!(manifest.find("ex3").unwrap().expected_error.as_deref(), Some("E0382"));
+        assert!(manifest.find("ex4").is_none());
+    }
+
+    #[test]
+    fn rejects_duplicates_and_missing_output() {
+        let twice = format!("{}{}", SAMPLE, SAMPLE);
+        assert!(Manifest::parse(&twice).unwrap_err().contains("listed twice"));
+
+        let no_output = SAMPLE.replace("expected_output = \"hello\"", "");
+        assert!(Manifest::parse(&no_output).unwrap_err().contains("expected_output"));
+        assert!(Manifest::parse("[[exercises]]\nname = \"x\"").is_err());
+
+        let no_code = SAMPLE.replace("expected_error = \"E0382\"", "");
+        assert!(Manifest::parse(&no_code).unwrap_err().contains("expected_error"));
+        let bad_code = SAMPLE.replace("\"E0382\"", "\"borrow of moved value\"");
+        assert!(Manifest::parse(&bad_code).unwrap_err().contains("expected_error"));
+    }
+
+    #[test]
+    fn the_shipped_manifest_is_valid() {
+        let root = find_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        let manifest = Manifest::load(&root).unwrap();
+
+        for exercise in &manifest.exercises {
+            assert!(root.join(&exercise.path).is_file(), "{} is missing", exercise.path.display());
+            assert!(!exercise.hint.trim().is_empty(), "{} has no hint", exercise.name);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// progress.rs
+// Which exercises the student has finished, kept in .rusty-progress.json at
+// the repository root.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::{Exercise, Manifest, Mode};
+
+pub const PROGRESS_FILE: &str = ".rusty-progress.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    done: BTreeSet<String>,
+}
+
+impl Progress {
+    /// A missing file just means nothing is done yet.
+    pub fn load(path: &Path) -> io::Result<Progress> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    pub fn is_done(&self, name: &str) -> bool {
+        self.done.contains(name)
+    }
+
+    /// Returns `false` if it was already marked.
+    pub fn mark_done(&mut self, name: &str) -> bool {
+        self.done.insert(name.to_string())
+    }
+
+    pub fn mark_pending(&mut self, name: &str) -> bool {
+        self.done.remove(name)
+    }
+
+    /// Marks an exercise done or not, depending on whether it just passed.
+    /// A compile_fail stage stays done once it passed, since the stage after
+    /// it fixes the same file.
+    pub fn record(&mut self, exercise: &Exercise, passed: bool) {
+        if passed {
+            self.mark_done(&exercise.name);
+        } else if exercise.mode != Mode::CompileFail {
+            self.mark_pending(&exercise.name);
+        }
+    }
+
+    /// Whether `verify` and watch mode should check `exercise` again. A
+    /// compile_fail stage that passed would only fail once its file is fixed.
+    pub fn needs_check(&self, exercise: &Exercise) -> bool {
+        exercise.mode != Mode::CompileFail || !self.is_done(&exercise.name)
+    }
+
+    /// The first exercise in manifest order that is not done yet.
+    pub fn next<'a>(&self, manifest: &'a Manifest) -> Option<&'a Exercise> {
+        manifest.exercises.iter().find(|exercise| !self.is_done(&exercise.name))
+    }
+
+    /// How many of the manifest's exercises are done.
+    pub fn count(&self, manifest: &Manifest) -> usize {
+        manifest.exercises.iter().filter(|exercise| self.is_done(&exercise.name)).count()
+    }
+}
+
+pub fn progress_path(root: &Path) -> PathBuf {
+    root.join(PROGRESS_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> Manifest {
+        Manifest::parse(
+            r#"
+[[exercises]]
+name = "a"
+path = "a.rs"
+mode = "compile"
+hint = "-"
+
+[[exercises]]
+name = "b"
+path = "b.rs"
+mode = "test"
+hint = "-"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn next_follows_manifest_order() {
+        let manifest = manifest();
+        let mut progress = Progress::default();
+        assert_eq!(progress.next(&manifest).unwrap().name, "a");
+
+        assert!(progress.mark_done("b"));
+        assert!(!progress.mark_done("b"));
+        assert_eq!(progress.next(&manifest).unwrap().name, "a");
+
+        progress.mark_done("a");
+        assert!(progress.next(&manifest).is_none());
+        assert_eq!(progress.count(&manifest), 2);
+    }
+
+    #[test]
+    fn compile_fail_stages_stay_done() {
+        let manifest = Manifest::parse(
+            r#"
+[[exercises]]
+name = "moved"
+path = "moved.rs"
+mode = "compile_fail"
+expected_error = "E0382"
+hint = "-"
+
+[[exercises]]
+name = "moved_fix"
+path = "moved.rs"
+mode = "test"
+hint = "-"
+"#,
+        )
+        .unwrap();
+        let (stage, fix) = (&manifest.exercises[0], &manifest.exercises[1]);
+        let mut progress = Progress::default();
+        assert!(progress.needs_check(stage));
+
+        progress.record(stage, true);
+        progress.record(fix, true);
+        // The fixed file compiles, so checking the first stage again fails
+        progress.record(stage, false);
+        progress.record(fix, false);
+
+        assert!(progress.is_done("moved"));
+        assert!(!progress.is_done("moved_fix"));
+        assert!(!progress.needs_check(stage));
+        assert!(progress.needs_check(fix));
+    }
+
+    #[test]
+    fn progress_survives_a_round_trip() {
+        let path = std::env::temp_dir().join(format!("rusty_progress_{}.json", std::process::id()));
+        assert_eq!(Progress::load(&path).unwrap(), Progress::default());
+
+        let mut progress = Progress::default();
+        progress.mark_done("a");
+        progress.save(&path).unwrap();
+        let loaded = Progress::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded.is_done("a"));
+        assert!(!loaded.is_done("b"));
+    }
+}
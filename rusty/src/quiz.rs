@@ -0,0 +1,283 @@
+// quiz.rs
+// The flashcards for `rusty quiz`: hand-written questions from
+// exercises/quiz.toml, plus questions generated from the snippets in
+// rust_cheatsheet.md.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+pub const QUIZ: &str = "exercises/quiz.toml";
+pub const CHEATSHEET: &str = "rust_cheatsheet.md";
+
+/// A subject the questions are grouped by, and the numbered example that
+/// teaches it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Topic {
+    pub name: String,
+    /// A file name in examples/ without `.rs`, e.g. "004-ownership_borrowing".
+    pub example: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Question {
+    /// Never change it once published: review history is kept by id.
+    pub id: String,
+    pub topic: String,
+    pub prompt: String,
+    /// Shown above the prompt.
+    #[serde(default)]
+    pub code: Option<String>,
+    pub choices: Vec<String>,
+    /// One of `choices`.
+    pub answer: String,
+    /// Shown after a wrong answer.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Bank {
+    pub topics: Vec<Topic>,
+    pub questions: Vec<Question>,
+}
+
+impl Bank {
+    pub fn parse(source: &str) -> Result<Bank, String> {
+        let bank: Bank = toml::from_str(source).map_err(|e| e.to_string())?;
+        bank.validate()?;
+        Ok(bank)
+    }
+
+    /// The questions in exercises/quiz.toml, followed by the ones generated
+    /// from the cheatsheet (if there is one).
+    pub fn load(root: &Path) -> Result<Bank, String> {
+        let path = root.join(QUIZ);
+        let source = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let mut bank = Bank::parse(&source).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if let Ok(markdown) = fs::read_to_string(root.join(CHEATSHEET)) {
+            bank.questions.extend(cheatsheet_questions(&markdown));
+            bank.validate().map_err(|e| format!("{}: {}", CHEATSHEET, e))?;
+        }
+        Ok(bank)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for question in &self.questions {
+            if !ids.insert(question.id.as_str()) {
+                return Err(format!("Question {} is listed twice", question.id));
+            }
+            if self.topic(&question.topic).is_none() {
+                return Err(format!("Question {} has an unknown topic: {}", question.id, question.topic));
+            }
+            if question.choices.len() < 2 {
+                return Err(format!("Question {} needs at least two choices", question.id));
+            }
+            if !question.choices.contains(&question.answer) {
+                return Err(format!("The answer to question {} is not one of its choices", question.id));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn topic(&self, name: &str) -> Option<&Topic> {
+        self.topics.iter().find(|topic| topic.name == name)
+    }
+}
+
+/// Two questions per ```rust block in the cheatsheet: which section it is
+/// from, and (unless it is marked `ignore`) what happens when it is built
+/// and run. The section's heading is the question's topic.
+pub fn cheatsheet_questions(markdown: &str) -> Vec<Question> {
+    let headings = headings(markdown);
+    let snippets: Vec<(snippets::Snippet, &str)> = snippets::extract(markdown)
+        .into_iter()
+        .filter_map(|snippet| {
+            let (_, heading) = headings.iter().rev().find(|(line, _)| *line < snippet.line)?;
+            Some((snippet, heading.as_str()))
+        })
+        .collect();
+    // Sections with at least one snippet; the wrong answers come from here.
+    let mut sections: Vec<&str> = Vec::new();
+    for (_, heading) in &snippets {
+        if !sections.contains(heading) {
+            sections.push(heading);
+        }
+    }
+
+    let mut questions = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    for (snippet, heading) in snippets {
+        let number = seen.iter().filter(|name| **name == heading).count() + 1;
+        seen.push(heading);
+        let id = format!("cheatsheet/{}/{}", slug(heading), number);
+
+        // The right section and the three after it in document order (wrapping
+        // around at the end), then sorted alphabetically.
+        let position = sections.iter().position(|name| *name == heading).unwrap();
+        let mut choices: Vec<String> = (0..4.min(sections.len()))
+            .map(|offset| sections[(position + offset) % sections.len()].to_string())
+            .collect();
+        choices.sort();
+        questions.push(Question {
+            id: format!("{}/section", id),
+            topic: heading.to_string(),
+            prompt: String::from("Which part of the cheatsheet is this from?"),
+            code: Some(snippet.code.clone()),
+            choices,
+            answer: heading.to_string(),
+            explanation: None,
+        });
+
+        if snippet.attributes.ignore {
+            continue;
+        }
+        let answer = if snippet.attributes.compile_fail {
+            BUILD_CHOICES[2]
+        } else if snippet.attributes.should_panic {
+            BUILD_CHOICES[1]
+        } else {
+            BUILD_CHOICES[0]
+        };
+        questions.push(Question {
+            id: format!("{}/builds", id),
+            topic: heading.to_string(),
+            prompt: String::from("Put inside `fn main`, what happens to this code?"),
+            code: Some(snippet.code),
+            choices: BUILD_CHOICES.iter().map(|choice| choice.to_string()).collect(),
+            answer: answer.to_string(),
+            explanation: None,
+        });
+    }
+    questions
+}
+
+const BUILD_CHOICES: [&str; 3] = ["It compiles and runs", "It compiles, then panics", "It does not compile"];
+
+/// The `##`/`###` headings with their line numbers, counting from 1.
+/// Headings inside code blocks do not count.
+fn headings(markdown: &str) -> Vec<(usize, String)> {
+    let mut headings = Vec::new();
+    let mut in_code = false;
+    for (index, line) in markdown.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+        } else if !in_code && line.starts_with("##") {
+            headings.push((index + 1, line.trim_start_matches('#').trim().to_string()));
+        }
+    }
+    headings
+}
+
+/// "Ownership & Borrowing" becomes "ownership-borrowing".
+fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHEET: &str = "\
+# Cheatsheet
+
+## Basics
+
+### Variables
+```rust
+let x = 5;
+```
+
+### Moves
+```rust
+let s = String::new();
+```
+
+```rust,compile_fail
+let s = String::new();
+drop(s);
+drop(s);
+```
+
+### Attributes
+```rust,ignore
+#[derive(Debug)]
+```
+
+## Commands
+```bash
+cargo test
+```
+";
+
+    #[test]
+    fn cheatsheet_snippets_become_questions() {
+        let questions = cheatsheet_questions(SHEET);
+        let ids: Vec<_> = questions.iter().map(|q| q.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "cheatsheet/variables/1/section",
+                "cheatsheet/variables/1/builds",
+                "cheatsheet/moves/1/section",
+                "cheatsheet/moves/1/builds",
+                "cheatsheet/moves/2/section",
+                "cheatsheet/moves/2/builds",
+                "cheatsheet/attributes/1/section",
+            ]
+        );
+
+        let section = &questions[2];
+        assert_eq!(section.topic, "Moves");
+        assert_eq!(section.answer, "Moves");
+        assert_eq!(section.choices, ["Attributes", "Moves", "Variables"]);
+        assert_eq!(questions[3].answer, "It compiles and runs");
+        assert_eq!(questions[5].answer, "It does not compile");
+    }
+
+    #[test]
+    fn banks_are_validated() {
+        let good = r#"
+[[topics]]
+name = "Collections"
+example = "008-collections"
+
+[[questions]]
+id = "sorted-keys"
+topic = "Collections"
+prompt = "Which collection keeps its keys sorted?"
+choices = ["HashMap", "BTreeMap"]
+answer = "BTreeMap"
+"#;
+        let bank = Bank::parse(good).unwrap();
+        assert_eq!(bank.topic("Collections").unwrap().example, "008-collections");
+
+        let twice = format!("{}{}", good, &good[good.find("[[questions]]").unwrap()..]);
+        assert!(Bank::parse(&twice).unwrap_err().contains("listed twice"));
+        let wrong_answer = good.replace("answer = \"BTreeMap\"", "answer = \"Vec\"");
+        assert!(Bank::parse(&wrong_answer).unwrap_err().contains("not one of its choices"));
+        let unknown_topic = good.replace("topic = \"Collections\"", "topic = \"Sorting\"");
+        assert!(Bank::parse(&unknown_topic).unwrap_err().contains("unknown topic"));
+    }
+
+    #[test]
+    fn the_shipped_bank_is_valid() {
+        let root = crate::manifest::find_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        let bank = Bank::load(&root).unwrap();
+
+        assert!(bank.questions.iter().any(|q| q.id.starts_with("cheatsheet/")));
+        for topic in &bank.topics {
+            let example = root.join("examples").join(format!("{}.rs", topic.example));
+            assert!(example.is_file(), "{} points to a missing example", topic.name);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// review.rs
+// What the student remembers, kept in .rusty-quiz.json at the repository
+// root: a Leitner box for every flashcard, so questions answered right come
+// back less and less often, and a tally of right answers per topic.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::quiz::{Bank, Question, Topic};
+
+pub const REVIEW_FILE: &str = ".rusty-quiz.json";
+
+/// Days until a card comes back, by how many times in a row it was answered
+/// right. A wrong answer starts it over.
+const INTERVALS: [u64; 6] = [1, 2, 4, 8, 16, 32];
+
+/// Topics need this many answers before their accuracy means anything.
+const MIN_ANSWERS: u32 = 3;
+
+/// Below this accuracy a topic is worth going over again.
+const WEAK: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Card {
+    /// Right answers in a row.
+    pub streak: usize,
+    /// The day (counted from 1970-01-01) it should be asked again.
+    pub due: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tally {
+    pub asked: u32,
+    pub right: u32,
+}
+
+impl Tally {
+    pub fn accuracy(&self) -> f64 {
+        if self.asked == 0 { 0.0 } else { f64::from(self.right) / f64::from(self.asked) }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    cards: BTreeMap<String, Card>,
+    topics: BTreeMap<String, Tally>,
+}
+
+impl Review {
+    /// A missing file just means no question was answered yet.
+    pub fn load(path: &Path) -> io::Result<Review> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Review::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// Up to `limit` questions to ask on `today`: the most overdue first,
+    /// then ones never asked, in bank order.
+    pub fn due<'a>(&self, bank: &'a Bank, today: u64, limit: usize) -> Vec<&'a Question> {
+        let mut due: Vec<&Question> = bank
+            .questions
+            .iter()
+            .filter(|question| self.cards.get(&question.id).is_none_or(|card| card.due <= today))
+            .collect();
+        // Never-asked cards sort as if they were due today.
+        due.sort_by_key(|question| self.cards.get(&question.id).map_or(today, |card| card.due));
+        due.truncate(limit);
+        due
+    }
+
+    /// The first day after `today` on which something is due.
+    pub fn next_due(&self, today: u64) -> Option<u64> {
+        self.cards.values().map(|card| card.due).filter(|due| *due > today).min()
+    }
+
+    pub fn record(&mut self, question: &Question, right: bool, today: u64) {
+        let card = self.cards.entry(question.id.clone()).or_default();
+        if right {
+            card.due = today + INTERVALS[card.streak.min(INTERVALS.len() - 1)];
+            card.streak += 1;
+        } else {
+            card.streak = 0;
+            card.due = today;
+        }
+
+        let tally = self.topics.entry(question.topic.clone()).or_default();
+        tally.asked += 1;
+        if right {
+            tally.right += 1;
+        }
+    }
+
+    pub fn card(&self, id: &str) -> Option<Card> {
+        self.cards.get(id).copied()
+    }
+
+    /// Every topic answered at least once, weakest first.
+    pub fn tallies(&self) -> Vec<(&str, Tally)> {
+        let mut tallies: Vec<(&str, Tally)> = self.topics.iter().map(|(name, tally)| (name.as_str(), *tally)).collect();
+        tallies.sort_by(|a, b| a.1.accuracy().total_cmp(&b.1.accuracy()).then(a.0.cmp(b.0)));
+        tallies
+    }
+
+    /// The topic most in need of another look: the weakest one with enough
+    /// answers to judge, if it is below 80%.
+    pub fn weakest<'a>(&self, bank: &'a Bank) -> Option<&'a Topic> {
+        self.tallies()
+            .into_iter()
+            .filter(|(_, tally)| tally.asked >= MIN_ANSWERS && tally.accuracy() < WEAK)
+            .find_map(|(name, _)| bank.topic(name))
+    }
+}
+
+pub fn review_path(root: &Path) -> PathBuf {
+    root.join(REVIEW_FILE)
+}
+
+/// Days since 1970-01-01.
+pub fn today() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() / 86_400).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bank() -> Bank {
+        Bank::parse(
+            r#"
+[[topics]]
+name = "Collections"
+example = "008-collections"
+
+[[topics]]
+name = "Lifetimes"
+example = "010-lifetimes"
+
+[[questions]]
+id = "a"
+topic = "Collections"
+prompt = "?"
+choices = ["yes", "no"]
+answer = "yes"
+
+[[questions]]
+id = "b"
+topic = "Lifetimes"
+prompt = "?"
+choices = ["yes", "no"]
+answer = "no"
+"#,
+        )
+        .unwrap()
+    }
+
+    fn ids(questions: Vec<&Question>) -> Vec<&str> {
+        questions.iter().map(|q| q.id.as_str()).collect()
+    }
+
+    #[test]
+    fn right_answers_come_back_later_and_later() {
+        let bank = bank();
+        let a = &bank.questions[0];
+        let mut review = Review::default();
+
+        review.record(a, true, 100);
+        assert_eq!(review.card("a").unwrap().due, 101);
+        review.record(a, true, 101);
+        review.record(a, true, 103);
+        assert_eq!(review.card("a").unwrap().due, 107);
+
+        review.record(a, false, 107);
+        assert_eq!(review.card("a").unwrap(), Card { streak: 0, due: 107 });
+    }
+
+    #[test]
+    fn due_puts_overdue_cards_before_new_ones() {
+        let bank = bank();
+        let mut review = Review::default();
+        assert_eq!(ids(review.due(&bank, 100, 10)), ["a", "b"]);
+
+        review.record(&bank.questions[1], true, 90);
+        assert_eq!(ids(review.due(&bank, 100, 10)), ["b", "a"]);
+        assert_eq!(ids(review.due(&bank, 100, 1)), ["b"]);
+
+        review.record(&bank.questions[0], true, 100);
+        review.record(&bank.questions[1], true, 100);
+        assert!(review.due(&bank, 100, 10).is_empty());
+        assert_eq!(review.next_due(100), Some(101));
+    }
+
+    #[test]
+    fn the_weakest_topic_needs_enough_answers() {
+        let bank = bank();
+        let (a, b) = (&bank.questions[0], &bank.questions[1]);
+        let mut review = Review::default();
+
+        review.record(b, false, 1);
+        review.record(b, false, 1);
+        assert_eq!(review.weakest(&bank), None);
+
+        review.record(b, true, 1);
+        for _ in 0..3 {
+            review.record(a, true, 1);
+        }
+        assert_eq!(review.weakest(&bank).unwrap().example, "010-lifetimes");
+        assert_eq!(review.tallies()[0], ("Lifetimes", Tally { asked: 3, right: 1 }));
+    }
+
+    #[test]
+    fn review_survives_a_round_trip() {
+        let bank = bank();
+        let path = std::env::temp_dir().join(format!("rusty_review_{}.json", std::process::id()));
+        assert_eq!(Review::load(&path).unwrap(), Review::default());
+
+        let mut review = Review::default();
+        review.record(&bank.questions[0], true, 5);
+        review.save(&path).unwrap();
+        let loaded = Review::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, review);
+    }
+}
@@ -0,0 +1,342 @@
+// runner.rs
+// Compiles an exercise with rustc, runs it, and decides whether it passes.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::manifest::{Exercise, Mode};
+
+/// Exercises start out with this line; deleting it says "check my work".
+pub const NOT_DONE: &str = "I AM NOT DONE";
+
+/// Exercises are small programs; anything slower is probably stuck in a loop.
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Passed,
+    /// Everything works, but the `I AM NOT DONE` marker is still in the file.
+    NotDone,
+    CompileError,
+    /// The program exited with an error, or a test failed.
+    Failed,
+    TimedOut,
+    WrongOutput { expected: String },
+    /// A compile_fail exercise that rustc accepted.
+    Compiled { expected: String },
+    /// A compile_fail exercise that rustc rejected for other reasons.
+    WrongError { expected: String, found: Vec<String> },
+}
+
+impl Verdict {
+    pub fn passed(&self) -> bool {
+        *self == Verdict::Passed
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Passed => write!(f, "✅ Passed"),
+            Verdict::NotDone => write!(f, "🚧 Works! Remove the \"{}\" line to move on", NOT_DONE),
+            Verdict::CompileError => write!(f, "❌ Does not compile"),
+            Verdict::Failed => write!(f, "❌ Failed"),
+            Verdict::TimedOut => write!(f, "⏰ Still running after {} seconds", RUN_TIMEOUT.as_secs()),
+            Verdict::WrongOutput { expected } => write!(f, "❌ Wrong output, expected:\n{}", expected),
+            Verdict::Compiled { expected } => {
+                write!(f, "❌ It compiles, but this exercise wants rustc to stop with error[{}]", expected)
+            }
+            Verdict::WrongError { expected, found } => {
+                write!(f, "❌ Expected only error[{}], got {}", expected, found.join(", "))
+            }
+        }
+    }
+}
+
+/// What happened when an exercise was checked.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub verdict: Verdict,
+    /// Compiler messages followed by whatever the program printed.
+    pub output: String,
+}
+
+/// Compiles and runs `exercise` the way its mode asks for.
+pub fn check(root: &Path, exercise: &Exercise) -> io::Result<Report> {
+    let source_path = root.join(&exercise.path);
+    let source = fs::read_to_string(&source_path)?;
+
+    let build_dir = root.join("target").join("rusty");
+    fs::create_dir_all(&build_dir)?;
+    let binary = build_dir.join(&exercise.name);
+
+    let mut rustc = Command::new(rustc());
+    rustc.args(["--edition", "2021", "--color", "never"]);
+    // The fix-it stage after a compile_fail exercise runs the same file's tests.
+    if matches!(exercise.mode, Mode::Test | Mode::CompileFail) {
+        rustc.arg("--test");
+    }
+    let compiled = rustc.arg("-o").arg(&binary).arg(&source_path).output()?;
+    let mut output = String::from_utf8_lossy(&compiled.stderr).into_owned();
+    if exercise.mode == Mode::CompileFail {
+        let expected = exercise.expected_error.clone().unwrap_or_default();
+        let verdict = if compiled.status.success() {
+            Verdict::Compiled { expected }
+        } else {
+            let found = errors(&output);
+            if found == [format!("error[{}]", expected)] {
+                done_or_not(&source)
+            } else {
+                Verdict::WrongError { expected, found }
+            }
+        };
+        return Ok(Report { verdict, output });
+    }
+    if !compiled.status.success() {
+        return Ok(Report { verdict: Verdict::CompileError, output });
+    }
+
+    let Some(run) = run_with_timeout(&binary)? else {
+        return Ok(Report { verdict: Verdict::TimedOut, output });
+    };
+    output.push_str(&run.stdout);
+    output.push_str(&run.stderr);
+
+    let verdict = if !run.success {
+        Verdict::Failed
+    } else if let (Mode::Output, Some(expected)) = (exercise.mode, &exercise.expected_output) {
+        if normalize(&run.stdout) == normalize(expected) {
+            done_or_not(&source)
+        } else {
+            Verdict::WrongOutput { expected: expected.trim_end().to_string() }
+        }
+    } else {
+        done_or_not(&source)
+    };
+    Ok(Report { verdict, output })
+}
+
+fn done_or_not(source: &str) -> Verdict {
+    if source.contains(NOT_DONE) { Verdict::NotDone } else { Verdict::Passed }
+}
+
+/// The distinct errors in rustc's messages, like `error[E0382]`, in the order
+/// they first appear. Errors without a code are just `error`.
+fn errors(diagnostics: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in diagnostics.lines() {
+        let error = if let Some(rest) = line.strip_prefix("error[") {
+            match rest.split_once(']') {
+                Some((code, _)) => format!("error[{}]", code),
+                None => continue,
+            }
+        } else if line.starts_with("error:") && !line.starts_with("error: aborting due to") {
+            String::from("error")
+        } else {
+            continue;
+        };
+        if !found.contains(&error) {
+            found.push(error);
+        }
+    }
+    found
+}
+
+/// Ignores trailing spaces and line-ending differences.
+fn normalize(text: &str) -> String {
+    text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n").trim_end().to_string()
+}
+
+/// `$RUSTC` if set, like Cargo does, otherwise whatever `rustc` is on the PATH.
+fn rustc() -> PathBuf {
+    std::env::var_os("RUSTC").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("rustc"))
+}
+
+struct Run {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+/// `None` if the program had to be killed.
+fn run_with_timeout(binary: &Path) -> io::Result<Option<Run>> {
+    let mut child = Command::new(binary)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Read both pipes on their own threads so a chatty program cannot block.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if started.elapsed() > RUN_TIMEOUT {
+            kill(&mut child);
+            break None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Ok(status.map(|status| Run { success: status.success(), stdout, stderr }))
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch repository with a single exercise file.
+    fn scratch(name: &str, source: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rusty_{}_{}", name, std::process::id()));
+        fs::create_dir_all(root.join("exercises")).unwrap();
+        fs::write(root.join("exercises").join(format!("{}.rs", name)), source).unwrap();
+        root
+    }
+
+    fn exercise(name: &str, mode: Mode, expected_output: Option<&str>) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: PathBuf::from(format!("exercises/{}.rs", name)),
+            mode,
+            hint: String::new(),
+            expected_output: expected_output.map(String::from),
+            expected_error: None,
+        }
+    }
+
+    #[test]
+    fn output_exercises_compare_what_they_print() {
+        let root = scratch("hello", "// I AM NOT DONE\nfn main() { println!(\"hello  \"); }\n");
+        let hello = exercise("hello", Mode::Output, Some("hello\n"));
+        assert_eq!(check(&root, &hello).unwrap().verdict, Verdict::NotDone);
+
+        let wrong = exercise("hello", Mode::Output, Some("goodbye"));
+        let expected = String::from("goodbye");
+        assert_eq!(check(&root, &wrong).unwrap().verdict, Verdict::WrongOutput { expected });
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn compile_errors_and_failing_tests_are_reported() {
+        let root = scratch("broken", "fn main() { let x: i32 = \"no\"; }\n");
+        let report = check(&root, &exercise("broken", Mode::Compile, None)).unwrap();
+        assert_eq!(report.verdict, Verdict::CompileError);
+        assert!(report.output.contains("mismatched types"));
+        fs::remove_dir_all(root).unwrap();
+
+        let root = scratch("tested", "#[test]\nfn adds() { assert_eq!(1 + 1, 3); }\n");
+        let report = check(&root, &exercise("tested", Mode::Test, None)).unwrap();
+        assert_eq!(report.verdict, Verdict::Failed);
+        assert!(report.output.contains("adds"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn compile_fail_exercises_want_exactly_one_error() {
+        let mut moved = exercise("moved", Mode::CompileFail, None);
+        moved.expected_error = Some(String::from("E0382"));
+
+        let use_after_move = "fn main() { let s = String::new(); drop(s); println!(\"{}\", s); }\n";
+        let root = scratch("moved", use_after_move);
+        assert_eq!(check(&root, &moved).unwrap().verdict, Verdict::Passed);
+        fs::remove_dir_all(root).unwrap();
+
+        let root = scratch("moved", &format!("// I AM NOT DONE\n{}", use_after_move));
+        assert_eq!(check(&root, &moved).unwrap().verdict, Verdict::NotDone);
+        fs::remove_dir_all(root).unwrap();
+
+        let two_errors = format!("{}fn other() {{ let n: i32 = \"1\"; }}\n", use_after_move);
+        let root = scratch("moved", &two_errors);
+        let found = vec![String::from("error[E0308]"), String::from("error[E0382]")];
+        let expected = String::from("E0382");
+        assert_eq!(check(&root, &moved).unwrap().verdict, Verdict::WrongError { expected, found });
+        fs::remove_dir_all(root).unwrap();
+
+        let root = scratch("moved", "#[test]\nfn fine() {}\n");
+        let expected = String::from("E0382");
+        assert_eq!(check(&root, &moved).unwrap().verdict, Verdict::Compiled { expected });
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn both_stages_of_a_compile_fail_exercise_verify() {
+        let mut moved = exercise("staged", Mode::CompileFail, None);
+        moved.expected_error = Some(String::from("E0382"));
+        let fix = Exercise { name: String::from("staged_fix"), mode: Mode::Test, ..exercise("staged", Mode::Test, None) };
+        let manifest = crate::Manifest { exercises: vec![moved, fix] };
+        let mut progress = crate::Progress::default();
+
+        let broken = "fn main() { let s = String::new(); drop(s); println!(\"{}\", s); }\n";
+        let root = scratch("staged", broken);
+        let first = &manifest.exercises[0];
+        progress.record(first, check(&root, first).unwrap().verdict.passed());
+
+        let fixed = "fn main() { let s = String::new(); println!(\"{}\", s); }\n#[test]\nfn fine() {}\n";
+        fs::write(root.join("exercises/staged.rs"), fixed).unwrap();
+        let second = &manifest.exercises[1];
+        progress.record(second, check(&root, second).unwrap().verdict.passed());
+        assert_eq!(progress.count(&manifest), 2);
+
+        // What `rusty verify` does once both stages are solved
+        for exercise in &manifest.exercises {
+            if !progress.needs_check(exercise) {
+                continue;
+            }
+            let passed = check(&root, exercise).unwrap().verdict.passed();
+            progress.record(exercise, passed);
+            assert!(passed, "{} no longer passes", exercise.name);
+        }
+        assert_eq!(progress.count(&manifest), 2);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn errors_are_listed_once_in_order() {
+        let diagnostics = "error[E0499]: cannot borrow\nerror: expected `;`\nerror[E0499]: again\n\
+                           error: aborting due to 3 previous errors\nSome errors have detailed explanations: E0499.\n";
+        assert_eq!(errors(diagnostics), ["error[E0499]", "error"]);
+    }
+
+    #[test]
+    fn shipped_exercises_compile_but_are_not_solved() {
+        let root = crate::manifest::find_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        let manifest = crate::Manifest::load(&root).unwrap();
+
+        for exercise in &manifest.exercises {
+            let report = check(&root, exercise).unwrap();
+            assert_ne!(report.verdict, Verdict::CompileError, "{}:\n{}", exercise.name, report.output);
+            assert!(!report.verdict.passed(), "{} passes before anyone has touched it", exercise.name);
+        }
+    }
+
+    #[test]
+    fn normalize_ignores_trailing_whitespace() {
+        assert_eq!(normalize("a  \r\nb\n\n"), "a\nb");
+    }
+}
@@ -0,0 +1,101 @@
+// watch.rs
+// The parts of watch mode that do not touch the file system: working out
+// which exercises a batch of file events is about, and drawing the screen.
+
+use std::path::PathBuf;
+
+use crate::manifest::{Exercise, Manifest};
+use crate::progress::Progress;
+use crate::runner::Report;
+
+/// Clears the terminal and moves the cursor to the top left.
+pub const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// The exercises behind `paths`, in manifest order and without repeats.
+/// Paths that are not exercises (editor swap files, say) are ignored, and so
+/// are compile_fail stages that are already done.
+pub fn changed_exercises<'a>(manifest: &'a Manifest, progress: &Progress, paths: &[PathBuf]) -> Vec<&'a Exercise> {
+    manifest
+        .exercises
+        .iter()
+        .filter(|exercise| paths.iter().any(|path| path.ends_with(&exercise.path)))
+        .filter(|exercise| progress.needs_check(exercise))
+        .collect()
+}
+
+/// One screenful for an exercise that was just checked: the compiler's and
+/// program's output, the verdict and, if it did not pass, the hint.
+pub fn render(exercise: &Exercise, report: &Report, done: usize, total: usize) -> String {
+    let mut screen = format!(
+        "👀 Watching {} ({}/{} done) · Ctrl-C to stop\n\n",
+        exercise.path.display(),
+        done,
+        total
+    );
+
+    let output = report.output.trim_end();
+    if !output.is_empty() {
+        screen.push_str(output);
+        screen.push_str("\n\n");
+    }
+    screen.push_str(&report.verdict.to_string());
+    screen.push('\n');
+
+    if !report.verdict.passed() {
+        screen.push_str(&format!("\n💡 Hint: {}\n", exercise.hint.trim()));
+    }
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::Verdict;
+
+    fn manifest() -> Manifest {
+        Manifest::parse(
+            r#"
+[[exercises]]
+name = "ex1"
+path = "exercises/ex1.rs"
+mode = "compile"
+hint = "Read the TODOs."
+
+[[exercises]]
+name = "ex2"
+path = "exercises/ex2.rs"
+mode = "test"
+hint = "-"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn events_map_to_exercises_in_order() {
+        let manifest = manifest();
+        let paths = [
+            PathBuf::from("/home/ann/rust/exercises/ex2.rs"),
+            PathBuf::from("/home/ann/rust/exercises/.ex1.rs.swp"),
+            PathBuf::from("/home/ann/rust/exercises/ex1.rs"),
+            PathBuf::from("/home/ann/rust/exercises/ex2.rs"),
+        ];
+        let names: Vec<_> = changed_exercises(&manifest, &Progress::default(), &paths).iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["ex1", "ex2"]);
+    }
+
+    #[test]
+    fn failures_show_the_diagnostics_and_the_hint() {
+        let manifest = manifest();
+        let exercise = &manifest.exercises[0];
+        let report = Report { verdict: Verdict::CompileError, output: String::from("error[E0308]: mismatched types\n") };
+
+        let screen = render(exercise, &report, 0, 2);
+        assert!(screen.starts_with("👀 Watching exercises/ex1.rs (0/2 done)"));
+        assert!(screen.contains("error[E0308]"));
+        assert!(screen.contains("💡 Hint: Read the TODOs."));
+
+        let passed = Report { verdict: Verdict::Passed, output: String::new() };
+        assert!(!render(exercise, &passed, 1, 2).contains("Hint"));
+    }
+}
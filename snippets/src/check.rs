@@ -0,0 +1,194 @@
+// check.rs
+// Compiles and runs one snippet and decides whether it did what its
+// attributes say it should.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::extract::{Snippet, program};
+
+/// Snippets are a few lines long; anything slower is stuck.
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// Marked `ignore`.
+    Ignored,
+    /// What went wrong, followed by rustc's or the program's messages.
+    Failed(String),
+}
+
+/// Builds `snippet` in `work_dir` (which should belong to it alone) and runs it.
+pub fn check(snippet: &Snippet, work_dir: &Path) -> io::Result<Outcome> {
+    let attributes = &snippet.attributes;
+    if attributes.ignore {
+        return Ok(Outcome::Ignored);
+    }
+
+    fs::create_dir_all(work_dir)?;
+    let source = work_dir.join("main.rs");
+    let binary = work_dir.join("main");
+    fs::write(&source, program(&snippet.code))?;
+
+    let compiled = Command::new(rustc())
+        .args(["--edition", "2021", "--color", "never", "-o"])
+        .arg(&binary)
+        .arg(&source)
+        .output()?;
+    let diagnostics = String::from_utf8_lossy(&compiled.stderr).into_owned();
+
+    if attributes.compile_fail {
+        let outcome = match &attributes.error_code {
+            _ if compiled.status.success() => Outcome::Failed(String::from("compiles, but it is marked compile_fail")),
+            Some(code) if !diagnostics.contains(&format!("error[{}]", code)) => {
+                Outcome::Failed(format!("does not compile, but not with error[{}]:\n{}", code, diagnostics))
+            }
+            _ => Outcome::Passed,
+        };
+        return Ok(outcome);
+    }
+    if !compiled.status.success() {
+        return Ok(Outcome::Failed(format!("does not compile:\n{}", diagnostics)));
+    }
+    if attributes.no_run {
+        return Ok(Outcome::Passed);
+    }
+
+    let Some((success, stderr)) = run(&binary, work_dir)? else {
+        return Ok(Outcome::Failed(format!("still running after {} seconds", RUN_TIMEOUT.as_secs())));
+    };
+    let outcome = match (success, attributes.should_panic) {
+        (true, false) | (false, true) => Outcome::Passed,
+        (true, true) => Outcome::Failed(String::from("runs fine, but it is marked should_panic")),
+        (false, false) => Outcome::Failed(format!("fails when run:\n{}", stderr)),
+    };
+    Ok(outcome)
+}
+
+/// `$RUSTC` if set, like Cargo does, otherwise whatever `rustc` is on the PATH.
+fn rustc() -> PathBuf {
+    std::env::var_os("RUSTC").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("rustc"))
+}
+
+/// Whether the program succeeded, and what it wrote to stderr. `None` if it
+/// had to be killed. Output goes to files so a chatty program cannot block.
+fn run(binary: &Path, work_dir: &Path) -> io::Result<Option<(bool, String)>> {
+    let stderr_path = work_dir.join("stderr.txt");
+    let mut child = Command::new(binary)
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .stdout(File::create(work_dir.join("stdout.txt"))?)
+        .stderr(File::create(&stderr_path)?)
+        .spawn()?;
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > RUN_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    Ok(Some((status.success(), fs::read_to_string(stderr_path)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::extract;
+
+    fn outcomes(markdown: &str, name: &str) -> Vec<Outcome> {
+        let dir = std::env::temp_dir().join(format!("snippets_{}_{}", name, std::process::id()));
+        let outcomes = extract(markdown)
+            .iter()
+            .map(|snippet| check(snippet, &dir.join(snippet.line.to_string())).unwrap())
+            .collect();
+        let _ = fs::remove_dir_all(dir);
+        outcomes
+    }
+
+    #[test]
+    fn attributes_decide_what_counts_as_passing() {
+        let markdown = "\
+```rust
+let v = vec![1, 2, 3];
+assert_eq!(v.len(), 3);
+```
+```rust,ignore
+this is not rust
+```
+```rust,should_panic
+let v: Vec<i32> = Vec::new();
+v[0];
+```
+```rust,compile_fail,E0382
+let s = String::new();
+drop(s);
+println!(\"{}\", s);
+```
+```rust,no_run
+loop {}
+```
+";
+        assert_eq!(
+            outcomes(markdown, "attributes"),
+            [Outcome::Passed, Outcome::Ignored, Outcome::Passed, Outcome::Passed, Outcome::Passed]
+        );
+    }
+
+    #[test]
+    fn failures_say_what_went_wrong() {
+        let markdown = "\
+```rust
+let x: i32 = \"five\";
+```
+```rust
+panic!(\"oops\");
+```
+```rust,should_panic
+let fine = 1;
+```
+```rust,compile_fail
+let fine = 1;
+```
+```rust,compile_fail,E0382
+let x: i32 = \"five\";
+```
+";
+        let messages: Vec<String> = outcomes(markdown, "failures")
+            .into_iter()
+            .map(|outcome| match outcome {
+                Outcome::Failed(message) => message,
+                other => panic!("expected a failure, got {:?}", other),
+            })
+            .collect();
+        assert!(messages[0].starts_with("does not compile:") && messages[0].contains("mismatched types"));
+        assert!(messages[1].starts_with("fails when run:") && messages[1].contains("oops"));
+        assert!(messages[2].contains("should_panic"));
+        assert!(messages[3].contains("compile_fail"));
+        assert!(messages[4].contains("not with error[E0382]"));
+    }
+
+    #[test]
+    fn the_repository_docs_pass() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        for file in ["README.md", "rust_cheatsheet.md"] {
+            let markdown = fs::read_to_string(root.join(file)).unwrap();
+            let dir = std::env::temp_dir().join(format!("snippets_docs_{}", std::process::id()));
+            for snippet in extract(&markdown) {
+                let outcome = check(&snippet, &dir.join(snippet.line.to_string())).unwrap();
+                assert!(!matches!(outcome, Outcome::Failed(_)), "{}:{}: {:?}", file, snippet.line, outcome);
+            }
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
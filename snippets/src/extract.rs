@@ -0,0 +1,196 @@
+// extract.rs
+// Finds the ```rust blocks in a markdown file and turns each one into a
+// program that rustc can build.
+
+/// What the info string after ```rust asks for, as in rustdoc:
+/// ```rust,ignore or ```rust,compile_fail,E0382.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attributes {
+    /// Not compiled at all.
+    pub ignore: bool,
+    /// Compiled, but not run.
+    pub no_run: bool,
+    /// Must not compile.
+    pub compile_fail: bool,
+    /// With `compile_fail`: the error code rustc must give, like `E0382`.
+    pub error_code: Option<String>,
+    /// Must panic when run.
+    pub should_panic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// The line of the opening fence, counting from 1.
+    pub line: usize,
+    pub code: String,
+    pub attributes: Attributes,
+}
+
+/// Every fenced block whose info string starts with `rust`. Other blocks
+/// (```bash, or no language at all) are not Rust and are skipped.
+pub fn extract(markdown: &str) -> Vec<Snippet> {
+    let mut snippets = Vec::new();
+    let mut lines = markdown.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let indent = line.len() - line.trim_start().len();
+        let Some((fence, info)) = opening_fence(line.trim_start()) else { continue };
+
+        let mut code = String::new();
+        for (_, line) in lines.by_ref() {
+            if is_closing_fence(line.trim(), &fence) {
+                break;
+            }
+            // Blocks inside list items are indented; the code is not.
+            let dedented = match line.get(..indent) {
+                Some(prefix) if prefix.trim().is_empty() => &line[indent..],
+                _ => line.trim_start(),
+            };
+            code.push_str(dedented);
+            code.push('\n');
+        }
+
+        if let Some(attributes) = attributes(info) {
+            snippets.push(Snippet { line: index + 1, code, attributes });
+        }
+    }
+    snippets
+}
+
+/// The fence (``` or ~~~, possibly longer) and the info string after it.
+fn opening_fence(line: &str) -> Option<(String, &str)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = line.chars().take_while(|c| *c == marker).count();
+    if length < 3 {
+        return None;
+    }
+    let fence = marker.to_string().repeat(length);
+    Some((fence, line[length..].trim()))
+}
+
+fn is_closing_fence(line: &str, fence: &str) -> bool {
+    let marker = fence.chars().next().unwrap();
+    line.len() >= fence.len() && line.chars().all(|c| c == marker)
+}
+
+/// `None` if the block is not Rust.
+fn attributes(info: &str) -> Option<Attributes> {
+    let mut tokens = info.split([',', ' ', '\t']).filter(|token| !token.is_empty());
+    if tokens.next()? != "rust" {
+        return None;
+    }
+
+    let mut attributes = Attributes::default();
+    for token in tokens {
+        match token {
+            "ignore" => attributes.ignore = true,
+            "no_run" => attributes.no_run = true,
+            "compile_fail" => attributes.compile_fail = true,
+            "should_panic" => attributes.should_panic = true,
+            code if is_error_code(code) => attributes.error_code = Some(code.to_string()),
+            // rustdoc knows more (edition2021, test_harness...); they do not matter here.
+            _ => {}
+        }
+    }
+    Some(attributes)
+}
+
+fn is_error_code(token: &str) -> bool {
+    token.len() == 5 && token.starts_with('E') && token[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// The snippet as a whole program, built the way rustdoc does it: warnings
+/// about unused code are off, and unless the snippet has its own `fn main`,
+/// it becomes the body of one. Crate attributes (`#![...]`) stay outside.
+pub fn program(code: &str) -> String {
+    let mut program = String::from("#![allow(unused)]\n");
+    let (crate_attributes, body): (Vec<&str>, Vec<&str>) =
+        code.lines().partition(|line| line.trim_start().starts_with("#!["));
+    for attribute in crate_attributes {
+        program.push_str(attribute);
+        program.push('\n');
+    }
+
+    let has_main = body.iter().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("fn main(") || line.starts_with("pub fn main(")
+    });
+    if has_main {
+        program.push_str(&body.join("\n"));
+        program.push('\n');
+    } else {
+        program.push_str("fn main() {\n");
+        for line in body {
+            program.push_str("    ");
+            program.push_str(line);
+            program.push('\n');
+        }
+        program.push_str("}\n");
+    }
+    program
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LESSON: &str = "\
+# Lesson
+
+```rust
+let x = 5;
+```
+
+```bash
+cargo run
+```
+
+1. A list item:
+   ```rust,should_panic
+   panic!(\"boom\");
+   ```
+
+````rust,compile_fail,E0382
+let s = String::new();
+drop(s);
+```
+println!(\"{}\", s);
+````
+
+```
+plain text
+```
+";
+
+    #[test]
+    fn only_rust_blocks_are_extracted() {
+        let snippets = extract(LESSON);
+        let lines: Vec<_> = snippets.iter().map(|s| s.line).collect();
+        assert_eq!(lines, [3, 12, 16]);
+
+        assert_eq!(snippets[0].code, "let x = 5;\n");
+        assert_eq!(snippets[0].attributes, Attributes::default());
+    }
+
+    #[test]
+    fn attributes_and_indentation_are_understood() {
+        let snippets = extract(LESSON);
+        assert!(snippets[1].attributes.should_panic);
+        assert_eq!(snippets[1].code, "panic!(\"boom\");\n");
+
+        // A longer fence can contain a shorter one.
+        let compile_fail = &snippets[2];
+        assert!(compile_fail.attributes.compile_fail);
+        assert_eq!(compile_fail.attributes.error_code.as_deref(), Some("E0382"));
+        assert!(compile_fail.code.contains("```\nprintln!"));
+    }
+
+    #[test]
+    fn fragments_get_a_main_but_programs_keep_theirs() {
+        let fragment = program("#![allow(dead_code)]\nlet x = 1;\n");
+        assert_eq!(fragment, "#![allow(unused)]\n#![allow(dead_code)]\nfn main() {\n    let x = 1;\n}\n");
+
+        let whole = program("fn helper() {}\n\nfn main() {\n    helper();\n}\n");
+        assert_eq!(whole, "#![allow(unused)]\nfn helper() {}\n\nfn main() {\n    helper();\n}\n");
+    }
+}
@@ -0,0 +1,9 @@
+// lib.rs
+// Checks the ```rust blocks in markdown files the way rustdoc checks doc
+// comments: each block becomes a program that is compiled and run.
+
+pub mod check;
+pub mod extract;
+
+pub use check::{Outcome, check};
+pub use extract::{Attributes, Snippet, extract, program};
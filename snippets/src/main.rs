@@ -0,0 +1,85 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use snippets::{Outcome, check, extract};
+
+const USAGE: &str = "\
+Usage: snippets [FILE.md ...]
+
+Compiles and runs every ```rust block in the given markdown files (default:
+every .md file in the current directory). Blocks can be marked like in
+rustdoc: ```rust,ignore  ```rust,no_run  ```rust,should_panic
+```rust,compile_fail  ```rust,compile_fail,E0382";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Some(flag) = args.iter().find(|arg| arg.starts_with('-')) {
+        eprintln!("❌ Unknown option: {}", flag);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+
+    let files: Vec<PathBuf> = if args.is_empty() { markdown_files() } else { args.iter().map(PathBuf::from).collect() };
+    let work_dir = env::temp_dir().join(format!("snippets_{}", process::id()));
+
+    let (mut total, mut ignored, mut failed) = (0, 0, 0);
+    for file in &files {
+        let markdown = fs::read_to_string(file).unwrap_or_else(|e| {
+            eprintln!("❌ Could not read {}: {}", file.display(), e);
+            process::exit(2);
+        });
+        let snippets = extract(&markdown);
+        println!("📄 {} ({} snippets)", file.display(), snippets.len());
+
+        for (index, snippet) in snippets.iter().enumerate() {
+            total += 1;
+            let dir = work_dir.join(total.to_string());
+            let outcome = check(snippet, &dir).unwrap_or_else(|e| {
+                eprintln!("❌ Could not check {}:{}: {}", file.display(), snippet.line, e);
+                process::exit(2);
+            });
+            let label = format!("#{} (line {})", index + 1, snippet.line);
+            match outcome {
+                Outcome::Passed => println!("  ✅ {}", label),
+                Outcome::Ignored => {
+                    ignored += 1;
+                    println!("  ⏭️  {} ignored", label);
+                }
+                Outcome::Failed(message) => {
+                    failed += 1;
+                    println!("  ❌ {} {}", label, indent(message.trim_end()));
+                }
+            }
+        }
+    }
+    let _ = fs::remove_dir_all(&work_dir);
+
+    println!();
+    if failed == 0 {
+        println!("🎉 {} snippets pass ({} ignored)", total - ignored, ignored);
+    } else {
+        println!("❌ {} of {} snippets failed", failed, total);
+        process::exit(1);
+    }
+}
+
+/// Every `.md` file in the current directory, sorted by name.
+fn markdown_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(".")
+        .map(|entries| entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect())
+        .unwrap_or_default();
+    files.retain(|path| path.extension().is_some_and(|ext| ext == "md") && path.is_file());
+    files.sort();
+    files.iter().map(|path| path.strip_prefix(".").unwrap_or(path)).map(Path::to_path_buf).collect()
+}
+
+/// Indents every line after the first, so a failure's details sit under it.
+fn indent(text: &str) -> String {
+    text.lines().collect::<Vec<_>>().join("\n      ")
+}